@@ -1,23 +1,23 @@
 #!/bin/bash
 
-for src in ./src/as/*; do
-  (
-    cd "$src"
+index() {
+  cargo run --quiet --release --manifest-path ./src/rust/zh-index/Cargo.toml -- "$@"
+}
 
-    if [ "$(cat ./res/source.json | grep 'deprecated' | awk '{print $2}')" == "true" ]; then
-      rm -rf ./build/package.aix
-    else
-      npm run build
-    fi
-  )
+# Fail before building anything if a source.json is invalid, two sources
+# share an id, or a version went back.
+index check || exit 1
+
+for src in $(index sources --deprecated); do
+  rm -rf "$src/build/package.aix" "$src/package.aix"
 done
 
-for src in ./src/rust/*; do
+for src in $(index sources); do
   (
     cd "$src"
 
-    if [ "$(cat ./res/source.json | grep 'deprecated' | awk '{print $2}')" == "true" ]; then
-      rm -rf ./package.aix
+    if [ -f ./package.json ]; then
+      npm run build
     else
       ./build.sh -a
     fi
@@ -25,3 +25,4 @@ for src in ./src/rust/*; do
 done
 
 aidoku build ./src/**/*.aix
+index write
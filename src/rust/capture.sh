@@ -0,0 +1,67 @@
+#!/bin/bash
+# Records a live response as a fixture of a source and adds its route to
+# tests/fixtures/fixtures.json. JSON responses are trimmed to the first few
+# items of every array, so fixtures stay small but keep the fields and nesting
+# the site sends. HTML is saved as is; cut it down by hand to the parts the
+# parser reads.
+#
+# usage: ./capture.sh zh.source file url [curl args ...]
+#
+#   ITEMS=3  items kept of each JSON array (default 3)
+#   BODY=…   substring the route matches in the request body, for POST APIs
+#
+# example:
+#   BODY='"operationName":"comicById"' ./capture.sh zh.komiic comic_by_id.json \
+#     https://komiic.com/api/query -X POST -H 'Content-Type: application/json' -d @query.json
+
+set -e
+cd "$(dirname "$0")"
+
+if [ $# -lt 3 ]; then
+  echo "usage: $0 zh.source file url [curl args ...]" >&2
+  exit 1
+fi
+
+src=${1%/}
+file=$2
+url=$3
+shift 3
+
+dir="$src/tests/fixtures"
+mkdir -p "$dir"
+[ -f "$dir/fixtures.json" ] || echo '[]' >"$dir/fixtures.json"
+
+method=GET
+args=("$@")
+for ((i = 0; i < ${#args[@]}; i++)); do
+  case ${args[$i]} in
+    -X | --request) method=${args[$((i + 1))]} ;;
+    -d | --data | --data-raw | --data-binary) [ "$method" = GET ] && method=POST ;;
+  esac
+done
+
+tmp=$(mktemp)
+trap 'rm -f "$tmp"' EXIT
+status=$(curl -sS -L -o "$tmp" -w '%{http_code}' "$url" "$@")
+
+if jq -e . "$tmp" >/dev/null 2>&1; then
+  jq --argjson n "${ITEMS:-3}" '
+    def trim: if type == "array" then .[:$n] | map(trim)
+      elif type == "object" then map_values(trim)
+      else . end;
+    trim' "$tmp" >"$dir/$file"
+else
+  cp "$tmp" "$dir/$file"
+fi
+
+route=$(jq -n --arg method "$method" --arg url "$url" --arg body "${BODY:-}" \
+  --arg file "$file" --argjson status "$status" '
+  (if $method != "GET" then {method: $method} else {} end)
+  + {url: $url}
+  + (if $body != "" then {body: $body} else {} end)
+  + (if $status != 200 then {status: $status} else {} end)
+  + {file: $file}')
+jq --argjson route "$route" '. + [$route]' "$dir/fixtures.json" >"$tmp"
+cp "$tmp" "$dir/fixtures.json"
+
+echo "$status $method $url -> $dir/$file"
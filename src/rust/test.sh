@@ -0,0 +1,27 @@
+#!/bin/bash
+# Runs the tests of the Rust sources on the host. The aidoku dependency is
+# patched to the native stand-in in ./zh-test, so no wasm target or network
+# is needed.
+#
+# usage: ./test.sh [zh.source ...]
+
+cd "$(dirname "$0")"
+
+host=$(rustc -vV | sed -n 's/^host: //p')
+patch="patch.\"https://github.com/Aidoku/aidoku-rs\".aidoku.path=\"$PWD/zh-test\""
+
+if [ $# -eq 0 ]; then
+  set -- zh-test zh-common zh-index zh.*
+fi
+
+status=0
+for src in "$@"; do
+  src=${src%/}
+  if [ ! -d "$src/tests" ]; then
+    continue
+  fi
+  echo "==> $src"
+  (cd "$src" && cargo test --target "$host" --config "$patch") || status=1
+done
+
+exit $status
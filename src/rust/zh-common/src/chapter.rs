@@ -0,0 +1,47 @@
+use aidoku::{
+	std::{String, Vec},
+	Chapter,
+};
+use alloc::boxed::Box;
+use once_cell::race::OnceBox;
+use regex::Regex;
+
+use crate::url::path_segments;
+
+/// Compiled on first use and kept, since it runs once per chapter title.
+static CHAPTER_NUMBER: OnceBox<Regex> = OnceBox::new();
+
+/// Extracts the number from titles like `第12话`, `第 3.5 章` or `１２卷`.
+/// Full-width digits are accepted.
+pub fn extract_chapter_number(title: &str) -> Option<f32> {
+	let re = CHAPTER_NUMBER.get_or_init(|| {
+		Box::new(
+			Regex::new(
+				r"(?:第\s*)([\d０-９]+(?:[\.．][\d０-９]+)?)|([\d０-９]+(?:[\.．][\d０-９]+)?)\s*(?:话|話|章|回|卷|册|冊)",
+			)
+			.unwrap(),
+		)
+	});
+	let captures = re.captures(title)?;
+	let num_match = captures.get(1).or_else(|| captures.get(2))?;
+	num_match
+		.as_str()
+		.chars()
+		.map(|c| match c {
+			'０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+			'．' => '.',
+			other => other,
+		})
+		.collect::<String>()
+		.parse::<f32>()
+		.ok()
+}
+
+/// Finds the chapter whose URL has the same path as `url`, so a deep link can
+/// be resolved when the chapter ID cannot be read from the link itself.
+pub fn find_chapter(chapters: Vec<Chapter>, url: &str) -> Option<Chapter> {
+	let path = path_segments(url);
+	chapters
+		.into_iter()
+		.find(|chapter| path_segments(&chapter.url) == path)
+}
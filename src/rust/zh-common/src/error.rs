@@ -0,0 +1,144 @@
+//! Errors that say what went wrong with the site.
+//!
+//! The legacy `AidokuError` carries a kind and no text, and the app only
+//! shows a generic failure for it. Helpers return an [`Error`] so that a
+//! [`SourceError`] keeps its message up to the entry point, and
+//! [`page_list`] shows it as a text page in the reader, the one place the app
+//! shows text from a source.
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind},
+	prelude::*,
+	std::{net::Request, String, Vec},
+	Page,
+};
+use alloc::string::ToString;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceError {
+	/// The username or password is missing, or the site rejected them.
+	Auth,
+	/// The account used up the site's request or image quota.
+	Quota,
+	/// The daily image quota has this many images left of `limit`, and
+	/// resets in `reset_in` seconds.
+	ImageQuota {
+		remaining: i32,
+		limit: i32,
+		reset_in: i32,
+	},
+	/// A Cloudflare challenge page came back instead of the content.
+	Cloudflare,
+	/// The response no longer has the expected structure.
+	Redesign,
+	/// The server failed with this 5xx status.
+	Server(i32),
+}
+
+impl SourceError {
+	pub fn message(&self) -> String {
+		match self {
+			SourceError::Auth => "登录失败，请在设置中检查用户名和密码".to_string(),
+			SourceError::Quota => "已达到站点的访问额度，请稍后再试或登录账号".to_string(),
+			SourceError::ImageQuota {
+				remaining,
+				limit,
+				reset_in,
+			} => format!(
+				"今日图片额度剩余 {}/{} 张，约 {} 小时后重置，登录账号可提高额度",
+				remaining,
+				limit,
+				(reset_in + 3599) / 3600
+			),
+			SourceError::Cloudflare => {
+				"被 Cloudflare 拦截，请在浏览器中打开站点完成验证后重试".to_string()
+			}
+			SourceError::Redesign => "站点页面结构已变更，请更新图源".to_string(),
+			SourceError::Server(status) => format!("站点服务器错误（{}），请稍后再试", status),
+		}
+	}
+
+	/// A text page with [`SourceError::message`], shown in the reader at
+	/// `index`.
+	pub fn page(&self, index: i32) -> Page {
+		Page {
+			index,
+			text: self.message(),
+			..Default::default()
+		}
+	}
+}
+
+impl From<SourceError> for AidokuError {
+	/// No legacy kind describes these failures, so all of them keep the
+	/// `DefaultNotFound` the sources returned before. It never matches
+	/// [`unsupported`](crate::link::unsupported).
+	fn from(_: SourceError) -> Self {
+		AidokuError {
+			reason: AidokuErrorKind::DefaultNotFound,
+		}
+	}
+}
+
+/// A failure in a source: a [`SourceError`] with a message for the user, or
+/// an `AidokuError` from the API, such as a failed value cast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+	Source(SourceError),
+	Aidoku(AidokuError),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl From<SourceError> for Error {
+	fn from(error: SourceError) -> Self {
+		Error::Source(error)
+	}
+}
+
+impl From<AidokuError> for Error {
+	fn from(error: AidokuError) -> Self {
+		Error::Aidoku(error)
+	}
+}
+
+impl From<Error> for AidokuError {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::Source(error) => error.into(),
+			Error::Aidoku(error) => error,
+		}
+	}
+}
+
+/// The result of `get_page_list`, where a [`SourceError`] becomes a single
+/// text page with its message instead of a failure without text.
+pub fn page_list(result: Result<Vec<Page>>) -> aidoku::error::Result<Vec<Page>> {
+	match result {
+		Err(Error::Source(error)) => Ok(Vec::from([error.page(0)])),
+		result => result.map_err(AidokuError::from),
+	}
+}
+
+/// Classifies a sent request by its status and headers. Statuses that the
+/// caller handles itself, such as a 404, are left alone.
+pub fn check_response(request: &Request) -> core::result::Result<(), SourceError> {
+	let status = request.status_code();
+	let header = |name: &str| {
+		request
+			.get_header(name)
+			.map(|value| value.read().to_ascii_lowercase())
+			.unwrap_or_default()
+	};
+
+	if header("cf-mitigated") == "challenge"
+		|| (matches!(status, 403 | 503) && header("server") == "cloudflare")
+	{
+		return Err(SourceError::Cloudflare);
+	}
+	match status {
+		401 => Err(SourceError::Auth),
+		429 => Err(SourceError::Quota),
+		500..=599 => Err(SourceError::Server(status)),
+		_ => Ok(()),
+	}
+}
@@ -0,0 +1,166 @@
+use aidoku::{
+	error::Result,
+	std::{json, String, Vec},
+	Filter, FilterType,
+};
+use alloc::string::ToString;
+
+/// Maps the index of a select filter to the site value, or to an empty string
+/// when the index is out of range.
+pub fn select_value(values: &[&str], index: usize) -> String {
+	values.get(index).copied().unwrap_or_default().to_string()
+}
+
+/// Reads the `(index, ascending)` pair of a sort filter.
+pub fn sort_value(filter: &Filter) -> Option<(usize, bool)> {
+	let value = filter.value.as_object().ok()?;
+	let index = value.get("index").as_int().ok()? as usize;
+	let ascending = value.get("ascending").as_bool().unwrap_or(false);
+	Some((index, ascending))
+}
+
+struct Decoded {
+	name: String,
+	ids: Vec<String>,
+	index: usize,
+}
+
+/// The filters passed to `get_manga_list`, decoded with the `ids` that
+/// `res/filters.json` lists next to the `options` of a select or sort filter.
+/// Adding an option to a filter then only touches `filters.json`.
+pub struct FilterValues {
+	query: String,
+	author: String,
+	ascending: bool,
+	values: Vec<Decoded>,
+	texts: Vec<(String, String)>,
+}
+
+impl FilterValues {
+	/// Decodes `filters` against `schema`, the source's `filters.json`
+	/// (usually `include_str!("../res/filters.json")`). Filters the app did not
+	/// send keep the option set as `default` in the schema.
+	pub fn decode(schema: &str, filters: Vec<Filter>) -> Result<Self> {
+		let mut decoded = FilterValues {
+			query: String::new(),
+			author: String::new(),
+			ascending: false,
+			values: Vec::new(),
+			texts: Vec::new(),
+		};
+
+		for item in json::parse(schema.as_bytes())?.as_array()? {
+			let item = item.as_object()?;
+			let name = match item.get("name").as_string() {
+				Ok(name) => name.read(),
+				Err(_) => continue,
+			};
+			let ids = match item.get("ids").as_array() {
+				Ok(ids) => ids
+					.map(|id| id.as_string().map(|id| id.read()).unwrap_or_default())
+					.collect::<Vec<_>>(),
+				Err(_) => Vec::new(),
+			};
+			let default = item.get("default");
+			let index = match default.as_object() {
+				Ok(default) => {
+					decoded.ascending = default.get("ascending").as_bool().unwrap_or(false);
+					default.get("index").as_int().unwrap_or(0)
+				}
+				Err(_) => default.as_int().unwrap_or(0),
+			};
+			decoded.values.push(Decoded {
+				name,
+				ids,
+				index: index as usize,
+			});
+		}
+
+		for filter in filters {
+			let index = match filter.kind {
+				FilterType::Title => {
+					decoded.query = filter.value.as_string()?.read();
+					continue;
+				}
+				FilterType::Author => {
+					decoded.author = filter.value.as_string()?.read();
+					continue;
+				}
+				FilterType::Text => {
+					decoded
+						.texts
+						.push((filter.name, filter.value.as_string()?.read()));
+					continue;
+				}
+				FilterType::Select => filter.value.as_int()? as usize,
+				FilterType::Sort => match sort_value(&filter) {
+					Some((index, ascending)) => {
+						decoded.ascending = ascending;
+						index
+					}
+					None => continue,
+				},
+				_ => continue,
+			};
+			if let Some(value) = decoded.values.iter_mut().find(|v| v.name == filter.name) {
+				value.index = index;
+			}
+		}
+
+		Ok(decoded)
+	}
+
+	/// The text of the title filter, empty when the user is not searching.
+	pub fn query(&self) -> String {
+		self.query.clone()
+	}
+
+	/// The text of the author filter, set when the user taps an author in the
+	/// manga details.
+	pub fn author(&self) -> String {
+		self.author.clone()
+	}
+
+	/// The trimmed text of the text filter `name`, empty when it is not set.
+	pub fn text(&self, name: &str) -> String {
+		self.texts
+			.iter()
+			.find(|(key, _)| key == name)
+			.map(|(_, value)| value.trim().to_string())
+			.unwrap_or_default()
+	}
+
+	/// The id of the option chosen in the select or sort filter `name`, or an
+	/// empty string when the filter has no `ids` or the index is out of range.
+	pub fn get(&self, name: &str) -> String {
+		self.values
+			.iter()
+			.find(|value| value.name == name)
+			.and_then(|value| value.ids.get(value.index))
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	/// The index of the option chosen in the select or sort filter `name`, for
+	/// filters whose value depends on another one and so has no `ids`.
+	pub fn index(&self, name: &str) -> Option<usize> {
+		self.values
+			.iter()
+			.find(|value| value.name == name)
+			.map(|value| value.index)
+	}
+
+	/// Every id of the select or sort filter `name`.
+	pub fn ids(&self, name: &str) -> &[String] {
+		self.values
+			.iter()
+			.find(|value| value.name == name)
+			.map(|value| value.ids.as_slice())
+			.unwrap_or_default()
+	}
+
+	/// Whether the sort filter is ascending.
+	pub fn ascending(&self) -> bool {
+		self.ascending
+	}
+}
@@ -0,0 +1,33 @@
+use aidoku::std::String;
+use alloc::string::ToString;
+
+/// Returns the last non-empty path segment, e.g. `abc` for `/manga/abc/`.
+pub fn last_segment<T: AsRef<str>>(url: T) -> String {
+	url.as_ref()
+		.split('/')
+		.rfind(|a| !a.is_empty())
+		.unwrap_or_default()
+		.to_string()
+}
+
+/// Returns the last path segment without its `.html` extension.
+pub fn html_slug<T: AsRef<str>>(url: T) -> String {
+	last_segment(url).replace(".html", "")
+}
+
+/// Splits an ID stored as `slug/mid` into its two parts. A missing part is
+/// returned as an empty string.
+pub fn split_pair(id: &str) -> (String, String) {
+	match id.split_once('/') {
+		Some((first, second)) => (first.to_string(), second.to_string()),
+		None => (id.to_string(), String::new()),
+	}
+}
+
+/// Joins the two parts of a `slug/mid` ID.
+pub fn join_pair(first: &str, second: &str) -> String {
+	let mut id = String::from(first);
+	id.push('/');
+	id.push_str(second);
+	id
+}
@@ -0,0 +1,17 @@
+//! JSON request bodies and response lists.
+//!
+//! Build bodies with [`json!`] instead of `format!` so that user input such as
+//! a search keyword or a password is always escaped.
+use aidoku::{
+	error::Result,
+	std::{ArrayRef, ObjectRef, Vec},
+};
+pub use serde_json::{json, Value};
+
+/// Parses every object of a response list with `parse`, skipping the items
+/// that are not objects or fail to parse, so that one malformed item does not
+/// fail the whole list.
+pub fn parse_list<T>(list: ArrayRef, parse: impl Fn(ObjectRef) -> Result<T>) -> Vec<T> {
+	list.filter_map(|item| parse(item.as_object().ok()?).ok())
+		.collect()
+}
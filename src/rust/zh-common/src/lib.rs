@@ -0,0 +1,17 @@
+//! Helpers shared by the Rust sources in this repository.
+//!
+//! Every source depends on this crate through a path dependency, so a site
+//! wide change (a new UA check, a different chapter title format) is fixed
+//! once here instead of in each `lib.rs`.
+#![no_std]
+extern crate alloc;
+
+pub mod chapter;
+pub mod error;
+pub mod filter;
+pub mod id;
+pub mod json;
+pub mod link;
+pub mod request;
+pub mod token;
+pub mod url;
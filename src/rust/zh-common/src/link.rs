@@ -0,0 +1,25 @@
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind},
+	std::String,
+	Chapter, DeepLink, Manga,
+};
+
+/// Builds the result of `handle_url` for a manga page or, when `chapter_id` is
+/// set, one of its chapters.
+pub fn deep_link(manga: Manga, chapter_id: Option<String>) -> DeepLink {
+	DeepLink {
+		manga: Some(manga),
+		chapter: chapter_id.map(|id| Chapter {
+			id,
+			..Default::default()
+		}),
+	}
+}
+
+/// The error returned by `handle_url` for links that point to neither a manga
+/// nor a chapter, such as listings or the home page.
+pub fn unsupported() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::Unimplemented,
+	}
+}
@@ -0,0 +1,53 @@
+use aidoku::std::{
+	defaults::defaults_get,
+	net::{HttpMethod, Request},
+	String,
+};
+
+pub const DESKTOP_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
+pub const MOBILE_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1";
+
+/// The headers a source sends with every request.
+pub struct Profile {
+	pub user_agent: &'static str,
+	pub headers: &'static [(&'static str, &'static str)],
+}
+
+impl Profile {
+	pub const DESKTOP: Profile = Profile {
+		user_agent: DESKTOP_UA,
+		headers: &[],
+	};
+	pub const MOBILE: Profile = Profile {
+		user_agent: MOBILE_UA,
+		headers: &[],
+	};
+
+	pub fn request<T: AsRef<str>>(&self, url: T, method: HttpMethod) -> Request {
+		self.apply(Request::new(url, method))
+	}
+
+	pub fn apply(&self, request: Request) -> Request {
+		let mut request = request.header("User-Agent", self.user_agent);
+		for (key, value) in self.headers {
+			request = request.header(key, value);
+		}
+		request
+	}
+}
+
+/// Builds a request with the desktop profile.
+pub fn gen_request<T: AsRef<str>>(url: T, method: HttpMethod) -> Request {
+	Profile::DESKTOP.request(url, method)
+}
+
+/// Returns the `User-Agent` setting, or the desktop UA when it is unset or
+/// empty.
+pub fn user_agent() -> String {
+	defaults_get("User-Agent")
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.ok()
+		.filter(|value| !value.is_empty())
+		.unwrap_or_else(|| String::from(DESKTOP_UA))
+}
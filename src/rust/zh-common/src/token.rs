@@ -0,0 +1,27 @@
+//! Expiry of the login tokens that sources keep in their defaults.
+use aidoku::std::{current_date, json};
+use base64::{engine::general_purpose, Engine};
+
+/// How long before its `exp` a token is already treated as expired, in
+/// seconds, so that it does not run out between the check and the request.
+pub const TOKEN_MARGIN: i64 = 60;
+
+/// Whether the `exp` claim of a JWT has passed, or is less than
+/// [`TOKEN_MARGIN`] away.
+///
+/// A token without a readable `exp`, whether it is not a JWT or the claim is
+/// missing, is never expired here: it is kept until the site rejects it with
+/// a 401, which every caller already handles by logging in again.
+pub fn is_expired(token: &str) -> bool {
+	let expiry = token
+		.split('.')
+		.nth(1)
+		.and_then(|payload| general_purpose::URL_SAFE_NO_PAD.decode(payload).ok())
+		.and_then(|payload| json::parse(payload).ok())
+		.and_then(|payload| payload.as_object().ok())
+		.and_then(|payload| payload.get("exp").as_int().ok());
+	match expiry {
+		Some(expiry) => expiry - TOKEN_MARGIN <= current_date() as i64,
+		None => false,
+	}
+}
@@ -0,0 +1,63 @@
+use aidoku::std::{String, Vec};
+use alloc::string::ToString;
+
+/// Unwraps cover URLs served through an image proxy such as
+/// `/_next/image?url=https%3A%2F%2Fcdn%2Fa.jpg&w=250&q=60`. Other URLs are
+/// returned unchanged.
+pub fn unproxy(url: String) -> String {
+	match url.rsplit_once("url=") {
+		Some((_, target)) => target
+			.split('&')
+			.next()
+			.unwrap_or_default()
+			.replace("%3A", ":")
+			.replace("%2F", "/"),
+		None => url,
+	}
+}
+
+/// Prefixes a protocol-relative or root-relative URL with `base`.
+pub fn absolute(base: &str, url: &str) -> String {
+	if url.starts_with("//") {
+		let mut absolute = String::from("https:");
+		absolute.push_str(url);
+		absolute
+	} else if url.starts_with('/') {
+		let mut absolute = String::from(base);
+		absolute.push_str(url);
+		absolute
+	} else {
+		url.to_string()
+	}
+}
+
+/// Returns the non-empty path segments of `url`, ignoring the scheme, host,
+/// query and fragment. `https://a.com/comic/abc/?p=1` gives `["comic", "abc"]`.
+pub fn path_segments(url: &str) -> Vec<String> {
+	let rest = match url.find("://") {
+		Some(index) => {
+			let rest = &url[index + 3..];
+			&rest[rest.find('/').unwrap_or(rest.len())..]
+		}
+		None => url,
+	};
+	rest.split(['?', '#'])
+		.next()
+		.unwrap_or_default()
+		.split('/')
+		.filter(|segment| !segment.is_empty())
+		.map(|segment| segment.to_string())
+		.collect()
+}
+
+/// Returns the value of the query parameter `name`, e.g. `12` for `id` in
+/// `/read?id=12&p=1`. The value is not percent-decoded.
+pub fn query_value(url: &str, name: &str) -> Option<String> {
+	let query = url.split('#').next()?.split_once('?')?.1;
+	query
+		.split('&')
+		.find_map(|pair| match pair.split_once('=') {
+			Some((key, value)) if key == name => Some(value.to_string()),
+			_ => None,
+		})
+}
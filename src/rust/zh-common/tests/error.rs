@@ -0,0 +1,88 @@
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind, ValueCastError},
+	harness::Harness,
+	std::net::{HttpMethod, Request},
+};
+use zh_common::{
+	error::{check_response, page_list, Error, SourceError},
+	link::unsupported,
+};
+
+fn check(url: &str) -> Result<(), SourceError> {
+	let request = Request::new(url, HttpMethod::Get);
+	request.send();
+	check_response(&request)
+}
+
+#[test]
+fn responses_are_classified() {
+	let _harness = Harness::new(env!("CARGO_MANIFEST_DIR")).fixtures("tests/fixtures");
+
+	assert_eq!(check("https://example.com/ok"), Ok(()));
+	assert_eq!(check("https://example.com/missing"), Ok(()));
+	assert_eq!(check("https://example.com/login"), Err(SourceError::Auth));
+	assert_eq!(check("https://example.com/limit"), Err(SourceError::Quota));
+	assert_eq!(
+		check("https://example.com/down"),
+		Err(SourceError::Server(502))
+	);
+	assert_eq!(
+		check("https://example.com/challenge"),
+		Err(SourceError::Cloudflare)
+	);
+	assert_eq!(
+		check("https://example.com/blocked"),
+		Err(SourceError::Cloudflare)
+	);
+}
+
+#[test]
+fn every_error_has_its_own_message() {
+	let errors = [
+		SourceError::Auth,
+		SourceError::Quota,
+		SourceError::ImageQuota {
+			remaining: 0,
+			limit: 300,
+			reset_in: 3600,
+		},
+		SourceError::Cloudflare,
+		SourceError::Redesign,
+		SourceError::Server(500),
+	];
+	for (i, a) in errors.iter().enumerate() {
+		for b in &errors[i + 1..] {
+			assert_ne!(a.message(), b.message());
+		}
+	}
+	assert!(SourceError::Server(502).message().contains("502"));
+	let quota = SourceError::ImageQuota {
+		remaining: 12,
+		limit: 300,
+		reset_in: 5400,
+	};
+	assert!(quota.message().contains("12/300"));
+	assert!(quota.message().contains("2 小时"));
+
+	let page = quota.page(3);
+	assert_eq!(page.index, 3);
+	assert_eq!(page.text, quota.message());
+}
+
+#[test]
+fn errors_are_not_unsupported_links() {
+	let error = AidokuError::from(SourceError::Server(500));
+	assert_eq!(error.reason, AidokuErrorKind::DefaultNotFound);
+	assert_ne!(error, unsupported());
+}
+
+#[test]
+fn page_list_shows_source_errors_as_text() {
+	let pages = page_list(Err(Error::Source(SourceError::Cloudflare))).unwrap();
+	assert_eq!(pages.len(), 1);
+	assert_eq!(pages[0].index, 0);
+	assert_eq!(pages[0].text, SourceError::Cloudflare.message());
+
+	let error = AidokuError::from(ValueCastError::NotArray);
+	assert_eq!(page_list(Err(Error::Aidoku(error))), Err(error));
+}
@@ -0,0 +1,64 @@
+use aidoku::harness::filter;
+use zh_common::filter::FilterValues;
+
+const SCHEMA: &str = r#"[
+	{ "type": "title" },
+	{ "type": "text", "name": "标签" },
+	{
+		"type": "select",
+		"name": "题材",
+		"options": ["全部", "爱情", "冒险"],
+		"ids": ["", "aiqing", "maoxian"]
+	},
+	{
+		"type": "select",
+		"name": "状态",
+		"options": ["全部", "连载", "完结"],
+		"ids": ["-1", "0", "1"],
+		"default": 1
+	},
+	{ "type": "select", "name": "语言", "options": ["全部", "汉化"] },
+	{
+		"type": "sort",
+		"name": "排序",
+		"options": ["更新", "热门"],
+		"ids": ["datetime_updated", "popular"],
+		"default": { "index": 1, "ascending": true }
+	}
+]"#;
+
+#[test]
+fn decode_reads_ids_by_filter_name() {
+	let filters = vec![
+		filter::title("妖神记"),
+		filter::author("发条动漫"),
+		filter::text("标签", " 热血 "),
+		filter::select("题材", 2),
+		filter::select("语言", 1),
+		filter::sort("排序", 0, false),
+	];
+	let filters = FilterValues::decode(SCHEMA, filters).unwrap();
+	assert_eq!(filters.query(), "妖神记");
+	assert_eq!(filters.author(), "发条动漫");
+	assert_eq!(filters.text("标签"), "热血");
+	assert_eq!(filters.get("题材"), "maoxian");
+	assert_eq!(filters.get("排序"), "datetime_updated");
+	assert!(!filters.ascending());
+	assert_eq!(filters.get("语言"), "");
+	assert_eq!(filters.index("语言"), Some(1));
+	assert_eq!(filters.ids("题材"), ["", "aiqing", "maoxian"]);
+}
+
+#[test]
+fn decode_falls_back_to_defaults() {
+	let filters = FilterValues::decode(SCHEMA, vec![filter::select("题材", 9)]).unwrap();
+	assert_eq!(filters.query(), "");
+	assert_eq!(filters.author(), "");
+	assert_eq!(filters.text("标签"), "");
+	assert_eq!(filters.get("题材"), "");
+	assert_eq!(filters.get("状态"), "0");
+	assert_eq!(filters.get("排序"), "popular");
+	assert!(filters.ascending());
+	assert_eq!(filters.get("地区"), "");
+	assert_eq!(filters.index("地区"), None);
+}
@@ -0,0 +1,20 @@
+use aidoku::std::json::parse;
+use zh_common::json::{json, parse_list};
+
+#[test]
+fn json_escapes_user_input() {
+	let keyword = r#"a", "sort": "ld\"#;
+	assert_eq!(
+		json!({ "keyword": keyword, "sort": "dd" }).to_string(),
+		r#"{"keyword":"a\", \"sort\": \"ld\\","sort":"dd"}"#
+	);
+}
+
+#[test]
+fn parse_list_skips_malformed_items() {
+	let list = parse(r#"[{"id":"a"},"b",{"name":"c"},{"id":"d"}]"#)
+		.and_then(|value| value.as_array())
+		.unwrap();
+	let ids = parse_list(list, |item| Ok(item.get("id").as_string()?.read()));
+	assert_eq!(ids, ["a", "d"]);
+}
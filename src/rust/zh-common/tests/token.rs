@@ -0,0 +1,29 @@
+use aidoku::harness::Harness;
+use base64::{engine::general_purpose, Engine};
+use zh_common::token::is_expired;
+
+fn jwt(payload: &str) -> String {
+	format!(
+		"eyJhbGciOiJIUzI1NiJ9.{}.signature",
+		general_purpose::URL_SAFE_NO_PAD.encode(payload)
+	)
+}
+
+#[test]
+fn jwts_expire_a_minute_early() {
+	let _harness = Harness::new(env!("CARGO_MANIFEST_DIR")).date(1_700_000_000.0);
+
+	assert!(!is_expired(&jwt(r#"{"exp":1700003600}"#)));
+	assert!(!is_expired(&jwt(r#"{"exp":1700000061}"#)));
+	assert!(is_expired(&jwt(r#"{"exp":1700000060}"#)));
+	assert!(is_expired(&jwt(r#"{"exp":1699990000}"#)));
+}
+
+#[test]
+fn tokens_without_an_expiry_are_kept() {
+	let _harness = Harness::new(env!("CARGO_MANIFEST_DIR")).date(1_700_000_000.0);
+
+	assert!(!is_expired("0123456789abcdef"));
+	assert!(!is_expired(&jwt(r#"{"sub":"reader"}"#)));
+	assert!(!is_expired("a.not base64.c"));
+}
@@ -0,0 +1,23 @@
+use zh_common::url::{path_segments, query_value};
+
+#[test]
+fn path_segments_ignore_host_query_and_fragment() {
+	assert_eq!(
+		path_segments("https://www.mangacopy.com/comic/yaoshenji/?p=1#top"),
+		["comic", "yaoshenji"]
+	);
+	assert_eq!(
+		path_segments("/read/3015/100101.html"),
+		["read", "3015", "100101.html"]
+	);
+	assert!(path_segments("https://komiic.com").is_empty());
+}
+
+#[test]
+fn query_value_reads_one_parameter() {
+	let url = "https://manhuabika.com/pchapter/?cid=5821&chapter=3#page";
+	assert_eq!(query_value(url, "cid").as_deref(), Some("5821"));
+	assert_eq!(query_value(url, "chapter").as_deref(), Some("3"));
+	assert_eq!(query_value(url, "page"), None);
+	assert_eq!(query_value("https://manhuabika.com/", "cid"), None);
+}
@@ -0,0 +1,304 @@
+//! Consistency check between `res/filters.json` and the code reading it.
+//!
+//! Most sources list the site value of each option in the `ids` of the filter
+//! and read it by name with `filters.get("题材")` (see
+//! `zh_common::filter::FilterValues`). The rest turn the option index into a
+//! value with a const table such as `FILTER_THEME: [&str; 61]`, picked in a
+//! `match filter.name.as_str()` arm of `get_manga_list`. When `filters.json`
+//! gains an option or renames a filter without the ids, table or code
+//! following, the source panics or silently ignores the filter at runtime, so
+//! the check reads both sides and compares them.
+
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use syn::{
+	punctuated::Punctuated,
+	visit::{self, Visit},
+	Expr, ExprMatch, ExprMethodCall, ExprPath, ItemConst, Lit, Macro, Member, Pat, Token,
+};
+
+use crate::{Error, Source};
+
+#[derive(Deserialize)]
+struct Filter {
+	#[serde(rename = "type")]
+	kind: String,
+	name: Option<String>,
+	#[serde(default)]
+	options: Vec<String>,
+	ids: Option<Vec<String>>,
+	#[serde(default)]
+	filters: Vec<Filter>,
+}
+
+/// The `FILTER_*` tables of a source, where `get_manga_list` uses them, and
+/// the filters it reads by name.
+#[derive(Default)]
+struct Usage {
+	tables: HashMap<String, usize>,
+	arms: Vec<(String, Vec<String>)>,
+	sort: Vec<String>,
+	reads: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for Usage {
+	fn visit_item_const(&mut self, item: &'ast ItemConst) {
+		let name = item.ident.to_string();
+		if let (true, Expr::Array(array)) = (name.starts_with("FILTER_"), &*item.expr) {
+			self.tables.insert(name, array.elems.len());
+		}
+		visit::visit_item_const(self, item);
+	}
+
+	fn visit_expr_match(&mut self, expr: &'ast ExprMatch) {
+		let by_name = is_filter_name(&expr.expr);
+		for arm in &expr.arms {
+			if by_name {
+				for name in string_patterns(&arm.pat) {
+					self.arms.push((name, tables_in(&arm.body)));
+				}
+			} else if is_sort(&arm.pat) {
+				self.sort.extend(tables_in(&arm.body));
+			}
+		}
+		visit::visit_expr_match(self, expr);
+	}
+
+	fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+		if let Some(name) = filter_read(call) {
+			self.reads.push(name);
+		}
+		visit::visit_expr_method_call(self, call);
+	}
+
+	/// Looks into the arguments of macros such as `format!`, which syn keeps as
+	/// tokens.
+	fn visit_macro(&mut self, mac: &'ast Macro) {
+		if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+			for arg in &args {
+				self.visit_expr(arg);
+			}
+		}
+		visit::visit_macro(self, mac);
+	}
+}
+
+/// Matches `filters.get("name")`, `filters.index("name")`,
+/// `filters.ids("name")` and `filters.text("name")`.
+fn filter_read(call: &ExprMethodCall) -> Option<String> {
+	let Expr::Path(receiver) = &*call.receiver else {
+		return None;
+	};
+	let Some(Expr::Lit(arg)) = call.args.first() else {
+		return None;
+	};
+	let Lit::Str(name) = &arg.lit else {
+		return None;
+	};
+	let method = call.method.to_string();
+	(receiver.path.is_ident("filters") && ["get", "index", "ids", "text"].contains(&method.as_str()))
+		.then(|| name.value())
+}
+
+/// Matches `filter.name.as_str()`.
+fn is_filter_name(expr: &Expr) -> bool {
+	let Expr::MethodCall(call) = expr else {
+		return false;
+	};
+	let Expr::Field(field) = &*call.receiver else {
+		return false;
+	};
+	let Expr::Path(base) = &*field.base else {
+		return false;
+	};
+	call.method == "as_str"
+		&& base.path.is_ident("filter")
+		&& matches!(&field.member, Member::Named(name) if name == "name")
+}
+
+/// Matches `FilterType::Sort`.
+fn is_sort(pat: &Pat) -> bool {
+	match pat {
+		Pat::Path(path) => path.path.segments.last().is_some_and(|s| s.ident == "Sort"),
+		_ => false,
+	}
+}
+
+fn string_patterns(pat: &Pat) -> Vec<String> {
+	match pat {
+		Pat::Lit(lit) => match &lit.lit {
+			Lit::Str(value) => vec![value.value()],
+			_ => Vec::new(),
+		},
+		Pat::Or(or) => or.cases.iter().flat_map(string_patterns).collect(),
+		_ => Vec::new(),
+	}
+}
+
+fn tables_in(expr: &Expr) -> Vec<String> {
+	struct Tables(Vec<String>);
+
+	impl<'ast> Visit<'ast> for Tables {
+		fn visit_expr_path(&mut self, expr: &'ast ExprPath) {
+			if let Some(ident) = expr.path.get_ident() {
+				let name = ident.to_string();
+				if name.starts_with("FILTER_") && !self.0.contains(&name) {
+					self.0.push(name);
+				}
+			}
+		}
+	}
+
+	let mut tables = Tables(Vec::new());
+	tables.visit_expr(expr);
+	tables.0
+}
+
+fn flatten(filters: Vec<Filter>, out: &mut Vec<Filter>) {
+	for mut filter in filters {
+		flatten(std::mem::take(&mut filter.filters), out);
+		out.push(filter);
+	}
+}
+
+/// Compares a `filters.json` with the Rust files of the same source.
+pub fn validate(filters: &str, files: &[syn::File]) -> Result<(), String> {
+	let mut all = Vec::new();
+	flatten(
+		serde_json::from_str(filters).map_err(|err| err.to_string())?,
+		&mut all,
+	);
+
+	let mut usage = Usage::default();
+	for file in files {
+		usage.visit_file(file);
+	}
+	let arms = usage
+		.arms
+		.iter()
+		.map(|(name, tables)| (name.as_str(), tables))
+		.collect::<HashMap<_, _>>();
+
+	let compare = |filter: &Filter, label: &str, tables: &[String]| {
+		for table in tables {
+			match usage.tables.get(table) {
+				Some(&len) if len != filter.options.len() => {
+					return Err(format!(
+						"`{}` has {} values but {} has {} options",
+						table,
+						len,
+						label,
+						filter.options.len()
+					));
+				}
+				_ => {}
+			}
+		}
+		Ok(())
+	};
+
+	for filter in &all {
+		let name = filter.name.as_deref().unwrap_or_default();
+		let label = format!("{} filter `{}`", filter.kind, name);
+		if let Some(ids) = &filter.ids {
+			if ids.len() != filter.options.len() {
+				return Err(format!(
+					"{} has {} ids but {} options",
+					label,
+					ids.len(),
+					filter.options.len()
+				));
+			}
+		}
+		match filter.kind.as_str() {
+			"select" => match arms.get(name) {
+				Some(tables) => compare(filter, &label, tables)?,
+				None if usage.reads.iter().any(|read| read == name) => {}
+				None => return Err(format!("{} is not read by `get_manga_list`", label)),
+			},
+			"sort" => {
+				if filter.ids.is_some() && !usage.reads.iter().any(|read| read == name) {
+					return Err(format!("{} is not read by `get_manga_list`", label));
+				}
+				compare(filter, &label, &usage.sort)?
+			}
+			"text" if !usage.reads.iter().any(|read| read == name) => {
+				return Err(format!("{} is not read by `get_manga_list`", label));
+			}
+			_ => {}
+		}
+	}
+
+	for (name, _) in &usage.arms {
+		if !all
+			.iter()
+			.any(|filter| filter.name.as_deref() == Some(name))
+		{
+			return Err(format!(
+				"`filter.name` arm `{}` does not match any filter",
+				name
+			));
+		}
+	}
+	for name in &usage.reads {
+		if !all
+			.iter()
+			.any(|filter| filter.name.as_deref() == Some(name))
+		{
+			return Err(format!(
+				"`filters.get(\"{}\")` does not match any filter",
+				name
+			));
+		}
+	}
+
+	Ok(())
+}
+
+/// Checks a Rust source. Sources without `res/filters.json` or Rust code are
+/// skipped.
+pub fn check_filters(source: &Source) -> Result<(), Error> {
+	let path = source.dir.join("res/filters.json");
+	let filters = match fs::read_to_string(&path) {
+		Ok(text) => text,
+		Err(_) if !path.exists() => return Ok(()),
+		Err(err) => return Err(Error::Io(path, err)),
+	};
+
+	let files = rust_files(&source.dir.join("src"))?
+		.iter()
+		.map(|file| {
+			let text = fs::read_to_string(file).map_err(|err| Error::Io(file.clone(), err))?;
+			syn::parse_file(&text).map_err(|err| Error::Invalid(file.clone(), err.to_string()))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	if files.is_empty() {
+		return Ok(());
+	}
+
+	validate(&filters, &files).map_err(|reason| Error::Invalid(path, reason))
+}
+
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+	let entries = match fs::read_dir(dir) {
+		Ok(entries) => entries,
+		Err(_) if !dir.exists() => return Ok(Vec::new()),
+		Err(err) => return Err(Error::Io(dir.to_path_buf(), err)),
+	};
+	let mut files = Vec::new();
+	for entry in entries {
+		let path = entry
+			.map_err(|err| Error::Io(dir.to_path_buf(), err))?
+			.path();
+		if path.extension().is_some_and(|ext| ext == "rs") {
+			files.push(path);
+		}
+	}
+	files.sort();
+	Ok(files)
+}
@@ -0,0 +1,250 @@
+//! Source index generator.
+//!
+//! Reads the `res/source.json` of every source under `src/`, validates it and
+//! writes `public/index.json` and `public/index.min.json` from it, so the
+//! published versions always match the manifests they were built from.
+//! Deprecated sources are left out of the index, and the filters of the
+//! others are checked against their code (see [`filters`]).
+
+use std::{
+	collections::HashMap,
+	fmt, fs, io,
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub mod filters;
+
+/// The directories under `src/` that contain sources.
+const SOURCE_ROOTS: [&str; 2] = ["as", "rust"];
+
+#[derive(Debug)]
+pub enum Error {
+	Io(PathBuf, io::Error),
+	Json(PathBuf, serde_json::Error),
+	Invalid(PathBuf, String),
+	DuplicateId {
+		id: String,
+		first: PathBuf,
+		second: PathBuf,
+	},
+	VersionRegressed {
+		id: String,
+		published: u32,
+		version: u32,
+	},
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+			Error::Json(path, err) => write!(f, "{}: {}", path.display(), err),
+			Error::Invalid(path, reason) => write!(f, "{}: {}", path.display(), reason),
+			Error::DuplicateId { id, first, second } => write!(
+				f,
+				"{} is used by both {} and {}",
+				id,
+				first.display(),
+				second.display()
+			),
+			Error::VersionRegressed {
+				id,
+				published,
+				version,
+			} => write!(
+				f,
+				"{} went back from version {} to {}",
+				id, published, version
+			),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Info {
+	pub id: String,
+	pub lang: String,
+	pub name: String,
+	pub version: u32,
+	pub url: String,
+	pub nsfw: u8,
+}
+
+/// A source directory and its manifest.
+#[derive(Debug, Clone)]
+pub struct Source {
+	pub dir: PathBuf,
+	pub info: Info,
+	pub deprecated: bool,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+	info: Info,
+	#[serde(default)]
+	deprecated: bool,
+}
+
+/// One entry of `public/index.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+	pub id: String,
+	pub name: String,
+	pub file: String,
+	pub icon: String,
+	pub lang: String,
+	pub version: u32,
+	pub nsfw: u8,
+}
+
+impl From<&Info> for Entry {
+	fn from(info: &Info) -> Self {
+		Entry {
+			id: info.id.clone(),
+			name: info.name.clone(),
+			file: format!("{}-v{}.aix", info.id, info.version),
+			icon: format!("{}-v{}.png", info.id, info.version),
+			lang: info.lang.clone(),
+			version: info.version,
+			nsfw: info.nsfw,
+		}
+	}
+}
+
+/// Parses and validates the manifest of the source in `dir`.
+pub fn parse_source(dir: &Path, text: &str) -> Result<Source, Error> {
+	let path = dir.join("res/source.json");
+	let manifest: Manifest =
+		serde_json::from_str(text).map_err(|err| Error::Json(path.clone(), err))?;
+	let info = manifest.info;
+
+	let invalid = |reason: String| Err(Error::Invalid(path.clone(), reason));
+	match info.id.strip_prefix(&format!("{}.", info.lang)) {
+		Some(name) if !name.is_empty() => {}
+		_ => {
+			return invalid(format!(
+				"id `{}` does not start with `{}.`",
+				info.id, info.lang
+			))
+		}
+	}
+	if info.name.trim().is_empty() {
+		return invalid(String::from("name is empty"));
+	}
+	if info.version == 0 {
+		return invalid(String::from("version must start at 1"));
+	}
+	if info.nsfw > 2 {
+		return invalid(format!("nsfw must be 0, 1 or 2, not {}", info.nsfw));
+	}
+
+	Ok(Source {
+		dir: dir.to_path_buf(),
+		info,
+		deprecated: manifest.deprecated,
+	})
+}
+
+/// Loads every source under `<root>/src`, sorted by directory.
+pub fn load_sources(root: &Path) -> Result<Vec<Source>, Error> {
+	let mut dirs = Vec::new();
+	for name in SOURCE_ROOTS {
+		let parent = root.join("src").join(name);
+		let entries = match fs::read_dir(&parent) {
+			Ok(entries) => entries,
+			Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+			Err(err) => return Err(Error::Io(parent, err)),
+		};
+		for entry in entries {
+			let dir = entry.map_err(|err| Error::Io(parent.clone(), err))?.path();
+			if dir.join("res/source.json").is_file() {
+				dirs.push(dir);
+			}
+		}
+	}
+	dirs.sort();
+
+	let sources = dirs
+		.iter()
+		.map(|dir| {
+			let path = dir.join("res/source.json");
+			let text = fs::read_to_string(&path).map_err(|err| Error::Io(path, err))?;
+			parse_source(dir, &text)
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	check_ids(&sources)?;
+
+	Ok(sources)
+}
+
+/// Fails when two sources share an id, deprecated ones included, since the
+/// app would treat them as the same source.
+pub fn check_ids(sources: &[Source]) -> Result<(), Error> {
+	let mut seen: HashMap<&str, &Path> = HashMap::new();
+	for source in sources {
+		if let Some(first) = seen.insert(&source.info.id, &source.dir) {
+			return Err(Error::DuplicateId {
+				id: source.info.id.clone(),
+				first: first.to_path_buf(),
+				second: source.dir.clone(),
+			});
+		}
+	}
+	Ok(())
+}
+
+/// Builds the index of the active sources, sorted by id. `published` is the
+/// index currently deployed; a source whose version is lower than the one
+/// published would never reach users who already installed it.
+pub fn build_index(sources: &[Source], published: &[Entry]) -> Result<Vec<Entry>, Error> {
+	check_ids(sources)?;
+
+	let published = published
+		.iter()
+		.map(|entry| (entry.id.as_str(), entry.version))
+		.collect::<HashMap<_, _>>();
+	let mut index = Vec::new();
+	for source in sources.iter().filter(|source| !source.deprecated) {
+		let info = &source.info;
+		if let Some(&version) = published.get(info.id.as_str()) {
+			if info.version < version {
+				return Err(Error::VersionRegressed {
+					id: info.id.clone(),
+					published: version,
+					version: info.version,
+				});
+			}
+		}
+		index.push(Entry::from(info));
+	}
+	index.sort_by(|a, b| a.id.cmp(&b.id));
+
+	Ok(index)
+}
+
+/// Reads a published index. A missing file is an empty index.
+pub fn read_index(path: &Path) -> Result<Vec<Entry>, Error> {
+	match fs::read_to_string(path) {
+		Ok(text) => serde_json::from_str(&text).map_err(|err| Error::Json(path.to_path_buf(), err)),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+		Err(err) => Err(Error::Io(path.to_path_buf(), err)),
+	}
+}
+
+/// Writes `index.json` and `index.min.json` into `public`.
+pub fn write_index(public: &Path, index: &[Entry]) -> Result<(), Error> {
+	let files = [
+		("index.json", serde_json::to_string_pretty(index)),
+		("index.min.json", serde_json::to_string(index)),
+	];
+	for (name, text) in files {
+		let path = public.join(name);
+		let text = text.map_err(|err| Error::Json(path.clone(), err))?;
+		fs::write(&path, text).map_err(|err| Error::Io(path, err))?;
+	}
+	Ok(())
+}
@@ -0,0 +1,75 @@
+use std::{
+	env,
+	path::{Path, PathBuf},
+	process::ExitCode,
+};
+
+use zh_index::{
+	build_index, filters::check_filters, load_sources, read_index, write_index, Entry, Error,
+};
+
+const USAGE: &str = "usage: zh-index [--root <dir>] <command>
+
+commands:
+  check                   validate every source.json against public/index.json and the
+                          filters.json of every source against its code
+  sources [--deprecated]  print the directories of the active (or deprecated) sources
+  write                   check, then write public/index.json and public/index.min.json";
+
+fn main() -> ExitCode {
+	let mut args = env::args().skip(1).collect::<Vec<_>>();
+	let mut root = PathBuf::from(".");
+	if args.first().map(String::as_str) == Some("--root") && args.len() > 1 {
+		root = PathBuf::from(args.remove(1));
+		args.remove(0);
+	}
+
+	let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+	let result = match args.as_slice() {
+		["check"] => check(&root).map(|_| ()),
+		["sources"] => sources(&root, false),
+		["sources", "--deprecated"] => sources(&root, true),
+		["write"] => write(&root),
+		_ => {
+			eprintln!("{}", USAGE);
+			return ExitCode::from(2);
+		}
+	};
+
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(err) => {
+			eprintln!("error: {}", err);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+fn check(root: &Path) -> Result<Vec<Entry>, Error> {
+	let sources = load_sources(root)?;
+	for source in sources.iter().filter(|source| !source.deprecated) {
+		check_filters(source)?;
+	}
+	let published = read_index(&root.join("public/index.json"))?;
+	build_index(&sources, &published)
+}
+
+fn sources(root: &Path, deprecated: bool) -> Result<(), Error> {
+	for source in load_sources(root)? {
+		if source.deprecated == deprecated {
+			println!("{}", source.dir.display());
+		}
+	}
+	Ok(())
+}
+
+fn write(root: &Path) -> Result<(), Error> {
+	let index = check(root)?;
+	write_index(&root.join("public"), &index)?;
+	println!(
+		"wrote {} sources to {}",
+		index.len(),
+		root.join("public").display()
+	);
+	Ok(())
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+
+use zh_index::{
+	filters::{check_filters, validate},
+	load_sources,
+};
+
+const CODE: &str = r#"
+const FILTER_THEME: [&str; 3] = ["", "aiqing", "maoxian"];
+const FILTER_ORDER: [&str; 2] = ["popular", "datetime_updated"];
+
+fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
+	for filter in filters {
+		match filter.kind {
+			FilterType::Select => {
+				let index = filter.value.as_int()? as usize;
+				match filter.name.as_str() {
+					"题材" => {
+						theme = select_value(&FILTER_THEME, index);
+					}
+					_ => continue,
+				}
+			}
+			FilterType::Sort => {
+				let (index, _) = sort_value(&filter)?;
+				order = FILTER_ORDER[index];
+			}
+			_ => continue,
+		}
+	}
+}
+
+fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
+	match listing.name.as_str() {
+		"日榜" => {}
+		_ => {}
+	}
+}
+"#;
+
+fn check(filters: &str) -> Result<(), String> {
+	validate(filters, &[syn::parse_file(CODE).unwrap()])
+}
+
+#[test]
+fn repo_filters_match_their_tables() {
+	for source in load_sources(Path::new("../../..")).unwrap() {
+		if !source.deprecated {
+			check_filters(&source).unwrap();
+		}
+	}
+}
+
+#[test]
+fn matching_filters_pass() {
+	check(
+		r#"[
+			{"type": "title"},
+			{"type": "select", "name": "题材", "options": ["全部", "爱情", "冒险"]},
+			{"type": "sort", "name": "排序", "options": ["热门", "更新"]}
+		]"#,
+	)
+	.unwrap();
+}
+
+#[test]
+fn option_count_must_match_table() {
+	assert_eq!(
+		check(r#"[{"type": "select", "name": "题材", "options": ["全部", "爱情"]}]"#),
+		Err("`FILTER_THEME` has 3 values but select filter `题材` has 2 options".into())
+	);
+	assert_eq!(
+		check(
+			r#"[
+				{"type": "select", "name": "题材", "options": ["全部", "爱情", "冒险"]},
+				{"type": "sort", "name": "排序", "options": ["热门", "更新", "收藏"]}
+			]"#
+		),
+		Err("`FILTER_ORDER` has 2 values but sort filter `排序` has 3 options".into())
+	);
+}
+
+#[test]
+fn filter_names_must_match_arms() {
+	assert_eq!(
+		check(
+			r#"[
+				{"type": "select", "name": "题材", "options": ["全部", "爱情", "冒险"]},
+				{"type": "select", "name": "地区", "options": ["全部", "日本"]}
+			]"#
+		),
+		Err("select filter `地区` is not read by `get_manga_list`".into())
+	);
+	assert_eq!(
+		check(r#"[{"type": "select", "name": "类型", "options": ["全部", "爱情", "冒险"]}]"#),
+		Err("select filter `类型` is not read by `get_manga_list`".into())
+	);
+	assert_eq!(
+		check(r#"[{"type": "title"}]"#),
+		Err("`filter.name` arm `题材` does not match any filter".into())
+	);
+}
+
+const DECODED: &str = r#"
+fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let theme = filters.get("题材");
+	let order = filters.get("排序");
+}
+"#;
+
+fn check_decoded(filters: &str) -> Result<(), String> {
+	validate(filters, &[syn::parse_file(DECODED).unwrap()])
+}
+
+#[test]
+fn ids_must_match_options() {
+	check_decoded(
+		r#"[
+			{"type": "select", "name": "题材", "options": ["全部", "爱情"], "ids": ["", "aiqing"]},
+			{"type": "sort", "name": "排序", "options": ["热门"], "ids": ["popular"]}
+		]"#,
+	)
+	.unwrap();
+	assert_eq!(
+		check_decoded(
+			r#"[
+				{"type": "select", "name": "题材", "options": ["全部", "爱情"], "ids": [""]},
+				{"type": "sort", "name": "排序", "options": ["热门"], "ids": ["popular"]}
+			]"#
+		),
+		Err("select filter `题材` has 1 ids but 2 options".into())
+	);
+}
+
+#[test]
+fn read_filters_must_exist() {
+	assert_eq!(
+		check_decoded(r#"[{"type": "select", "name": "题材", "options": ["全部"], "ids": [""]}]"#),
+		Err("`filters.get(\"排序\")` does not match any filter".into())
+	);
+	assert_eq!(
+		check_decoded(
+			r#"[
+				{"type": "select", "name": "题材", "options": ["全部"], "ids": [""]},
+				{"type": "select", "name": "地区", "options": ["全部"], "ids": [""]},
+				{"type": "sort", "name": "排序", "options": ["热门"], "ids": ["popular"]}
+			]"#
+		),
+		Err("select filter `地区` is not read by `get_manga_list`".into())
+	);
+}
+
+#[test]
+fn text_filters_must_be_read() {
+	let code = r#"
+fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let tag = filters.text("标签");
+}
+"#;
+	let check = |filters: &str| validate(filters, &[syn::parse_file(code).unwrap()]);
+	check(r#"[{"type": "text", "name": "标签"}]"#).unwrap();
+	assert_eq!(
+		check(r#"[{"type": "text", "name": "标签"}, {"type": "text", "name": "作者"}]"#),
+		Err("text filter `作者` is not read by `get_manga_list`".into())
+	);
+	assert_eq!(
+		check(r#"[{"type": "title"}]"#),
+		Err("`filters.get(\"标签\")` does not match any filter".into())
+	);
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use zh_index::{build_index, parse_source, Entry, Error, Source};
+
+fn source(dir: &str, id: &str, version: u32, deprecated: bool) -> Source {
+	let text = format!(
+		r#"{{
+			"info": {{
+				"id": "{}",
+				"lang": "zh",
+				"name": "测试",
+				"version": {},
+				"url": "https://example.com",
+				"nsfw": 0
+			}},
+			"deprecated": {}
+		}}"#,
+		id, version, deprecated
+	);
+	parse_source(Path::new(dir), &text).unwrap()
+}
+
+fn entry(id: &str, version: u32) -> Entry {
+	Entry {
+		id: id.to_string(),
+		name: "测试".to_string(),
+		file: format!("{}-v{}.aix", id, version),
+		icon: format!("{}-v{}.png", id, version),
+		lang: "zh".to_string(),
+		version,
+		nsfw: 0,
+	}
+}
+
+#[test]
+fn index_skips_deprecated_and_sorts_by_id() {
+	let sources = [
+		source("src/rust/zh.b", "zh.b", 2, false),
+		source("src/rust/zh.c", "zh.c", 1, true),
+		source("src/as/zh.a", "zh.a", 1, false),
+	];
+	let index = build_index(&sources, &[entry("zh.b", 1)]).unwrap();
+	assert_eq!(index, [entry("zh.a", 1), entry("zh.b", 2)]);
+}
+
+#[test]
+fn index_rejects_duplicate_ids() {
+	let sources = [
+		source("src/rust/zh.mxshm", "zh.msxhm", 6, false),
+		source("src/rust/zh.msxhm", "zh.msxhm", 1, true),
+	];
+	assert!(matches!(
+		build_index(&sources, &[]),
+		Err(Error::DuplicateId { id, .. }) if id == "zh.msxhm"
+	));
+}
+
+#[test]
+fn index_rejects_version_regressions() {
+	let sources = [source("src/rust/zh.a", "zh.a", 2, false)];
+	assert!(matches!(
+		build_index(&sources, &[entry("zh.a", 3)]),
+		Err(Error::VersionRegressed {
+			published: 3,
+			version: 2,
+			..
+		})
+	));
+}
+
+#[test]
+fn manifests_are_validated() {
+	let invalid = [
+		r#"{"info": {"id": "a", "lang": "zh", "name": "测试", "version": 1, "url": "", "nsfw": 0}}"#,
+		r#"{"info": {"id": "zh.a", "lang": "zh", "name": " ", "version": 1, "url": "", "nsfw": 0}}"#,
+		r#"{"info": {"id": "zh.a", "lang": "zh", "name": "测试", "version": 0, "url": "", "nsfw": 0}}"#,
+		r#"{"info": {"id": "zh.a", "lang": "zh", "name": "测试", "version": 1, "url": "", "nsfw": 3}}"#,
+	];
+	for text in invalid {
+		assert!(matches!(
+			parse_source(Path::new("zh.a"), text),
+			Err(Error::Invalid(..))
+		));
+	}
+	assert!(matches!(
+		parse_source(Path::new("zh.a"), r#"{"info": {"id": "zh.a"}}"#),
+		Err(Error::Json(..))
+	));
+}
@@ -0,0 +1,48 @@
+//! Pass-through versions of the aidoku entry point attributes.
+//!
+//! On wasm the real macros wrap each function in an exported shim. Natively
+//! there is no host to call them, so the function is kept as written and made
+//! `pub` for the integration tests under `tests/`.
+
+use proc_macro::{Ident, Span, TokenStream, TokenTree};
+
+fn export(item: TokenStream) -> TokenStream {
+	let mut tokens = item.into_iter().collect::<Vec<TokenTree>>();
+	let is_pub = tokens
+		.iter()
+		.any(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "pub"));
+	if !is_pub {
+		let position = tokens
+			.iter()
+			.position(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"))
+			.unwrap_or(0);
+		tokens.insert(
+			position,
+			TokenTree::Ident(Ident::new("pub", Span::call_site())),
+		);
+	}
+	tokens.into_iter().collect()
+}
+
+macro_rules! entry_point {
+	($($name:ident),*) => {
+		$(
+			#[proc_macro_attribute]
+			pub fn $name(_: TokenStream, item: TokenStream) -> TokenStream {
+				export(item)
+			}
+		)*
+	};
+}
+
+entry_point!(
+	initialize,
+	get_manga_list,
+	get_manga_listing,
+	get_manga_details,
+	get_chapter_list,
+	get_page_list,
+	modify_image_request,
+	handle_url,
+	handle_notification
+);
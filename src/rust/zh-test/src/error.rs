@@ -0,0 +1,47 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueCastError {
+	NotArray,
+	NotObject,
+	NotString,
+	NotNumber,
+	NotBool,
+	NotNode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeError {
+	ParseError,
+	ModifyError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AidokuErrorKind {
+	ValueCast(ValueCastError),
+	Unimplemented,
+	DefaultNotFound,
+	NodeError(NodeError),
+	JsonParseError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AidokuError {
+	pub reason: AidokuErrorKind,
+}
+
+impl From<ValueCastError> for AidokuError {
+	fn from(error: ValueCastError) -> Self {
+		AidokuError {
+			reason: AidokuErrorKind::ValueCast(error),
+		}
+	}
+}
+
+impl From<NodeError> for AidokuError {
+	fn from(error: NodeError) -> Self {
+		AidokuError {
+			reason: AidokuErrorKind::NodeError(error),
+		}
+	}
+}
+
+pub type Result<T> = core::result::Result<T, AidokuError>;
@@ -0,0 +1,389 @@
+//! Control over the emulated host.
+//!
+//! Every test thread has its own runtime. [`Harness::new`] resets it and seeds
+//! the defaults store from the source's `res/settings.json`, so a test starts
+//! from the same state the app would after a fresh install.
+//!
+//! Responses come from a fixture directory containing a `fixtures.json`
+//! manifest:
+//!
+//! ```json
+//! [
+//!   { "url": "https://example.com/comic/1", "file": "details.html" },
+//!   { "method": "POST", "url": "https://example.com/api", "body": "comicById", "file": "details.json" }
+//! ]
+//! ```
+//!
+//! A route matches when the method (default `GET`) and the full URL are equal,
+//! the request body contains `body` if it is set, and the request carries
+//! every header in `request_headers`, such as a revoked `Authorization`. The
+//! first matching route wins. `status` (default 200) and the response
+//! `headers` are optional. A request without a matching route panics with
+//! its URL, which is the cue to record a new fixture.
+//!
+//! A JSON fixture can serve several routes that differ in a few fields: a
+//! route's `patch` is merged into the file as a JSON merge patch (RFC 7386)
+//! before it is served.
+//!
+//! ```json
+//! { "url": "https://example.com/api/comic/2", "file": "details.json", "patch": { "id": "2" } }
+//! ```
+//!
+//! [`Harness::golden`] compares a parsed value with `tests/golden/<name>.json`.
+//! Run the tests with `UPDATE_GOLDEN=1` to rewrite those files after an
+//! intended change, and review the diff before committing it.
+
+use ::std::{
+	cell::RefCell,
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	std::{net::HttpMethod, ObjectRef, StringRef, ValueRef},
+	Filter, FilterType,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct Response {
+	pub status: i32,
+	pub headers: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+/// A request as it reached the emulated network.
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+	pub method: HttpMethod,
+	pub url: String,
+	pub headers: Vec<(String, String)>,
+	pub body: Option<Vec<u8>>,
+}
+
+impl RecordedRequest {
+	pub fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.as_str())
+	}
+
+	pub fn body_string(&self) -> String {
+		String::from_utf8_lossy(self.body.as_deref().unwrap_or_default()).to_string()
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Route {
+	#[serde(default = "default_method")]
+	method: String,
+	url: String,
+	#[serde(default)]
+	body: Option<String>,
+	#[serde(default)]
+	request_headers: HashMap<String, String>,
+	#[serde(default = "default_status")]
+	status: i32,
+	#[serde(default)]
+	headers: HashMap<String, String>,
+	file: PathBuf,
+	#[serde(default)]
+	patch: Option<serde_json::Value>,
+}
+
+fn default_method() -> String {
+	String::from("GET")
+}
+
+fn default_status() -> i32 {
+	200
+}
+
+struct Runtime {
+	routes: Vec<(Route, PathBuf)>,
+	defaults: HashMap<String, ValueRef>,
+	requests: Vec<RecordedRequest>,
+	now: f64,
+}
+
+impl Default for Runtime {
+	fn default() -> Self {
+		Runtime {
+			routes: Vec::new(),
+			defaults: HashMap::new(),
+			requests: Vec::new(),
+			now: 1_700_000_000.0,
+		}
+	}
+}
+
+thread_local! {
+	static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::default());
+}
+
+fn method_name(method: HttpMethod) -> &'static str {
+	match method {
+		HttpMethod::Get => "GET",
+		HttpMethod::Post => "POST",
+		HttpMethod::Head => "HEAD",
+		HttpMethod::Put => "PUT",
+		HttpMethod::Delete => "DELETE",
+	}
+}
+
+pub(crate) fn respond(
+	method: HttpMethod,
+	url: &str,
+	headers: &[(String, String)],
+	body: Option<&[u8]>,
+) -> Response {
+	RUNTIME.with(|runtime| {
+		let mut runtime = runtime.borrow_mut();
+		runtime.requests.push(RecordedRequest {
+			method,
+			url: url.to_string(),
+			headers: headers.to_vec(),
+			body: body.map(|body| body.to_vec()),
+		});
+		let body_text = String::from_utf8_lossy(body.unwrap_or_default()).to_string();
+		let route = runtime.routes.iter().find(|(route, _)| {
+			route.method.eq_ignore_ascii_case(method_name(method))
+				&& route.url == url
+				&& route
+					.body
+					.as_ref()
+					.map(|needle| body_text.contains(needle.as_str()))
+					.unwrap_or(true)
+				&& route.request_headers.iter().all(|(name, value)| {
+					headers
+						.iter()
+						.any(|(key, sent)| key.eq_ignore_ascii_case(name) && sent == value)
+				})
+		});
+		let (route, dir) = match route {
+			Some(route) => route,
+			None => panic!(
+				"no fixture for {} {} (body: {:?})",
+				method_name(method),
+				url,
+				body_text
+			),
+		};
+		let path = dir.join(&route.file);
+		let mut data = fs::read(&path)
+			.unwrap_or_else(|error| panic!("cannot read fixture {}: {}", path.display(), error));
+		if let Some(patch) = &route.patch {
+			let mut value =
+				serde_json::from_slice::<serde_json::Value>(&data).unwrap_or_else(|error| {
+					panic!("cannot patch fixture {}: {}", path.display(), error)
+				});
+			merge_patch(&mut value, patch);
+			data = serde_json::to_vec(&value).unwrap();
+		}
+		Response {
+			status: route.status,
+			headers: route
+				.headers
+				.iter()
+				.map(|(key, value)| (key.clone(), value.clone()))
+				.collect(),
+			body: data,
+		}
+	})
+}
+
+/// Applies a JSON merge patch: objects are merged key by key, a `null`
+/// removes the key, and any other value, arrays included, replaces it.
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+	let serde_json::Value::Object(patch) = patch else {
+		*target = patch.clone();
+		return;
+	};
+	if !target.is_object() {
+		*target = serde_json::Value::Object(serde_json::Map::new());
+	}
+	let target = target.as_object_mut().unwrap();
+	for (key, value) in patch {
+		if value.is_null() {
+			target.remove(key);
+		} else {
+			merge_patch(
+				target.entry(key.clone()).or_insert(serde_json::Value::Null),
+				value,
+			);
+		}
+	}
+}
+
+pub(crate) fn default(key: &str) -> Option<ValueRef> {
+	RUNTIME.with(|runtime| runtime.borrow().defaults.get(key).cloned())
+}
+
+pub(crate) fn set_default(key: &str, value: ValueRef) {
+	RUNTIME.with(|runtime| {
+		runtime.borrow_mut().defaults.insert(key.to_string(), value);
+	});
+}
+
+pub(crate) fn now() -> f64 {
+	RUNTIME.with(|runtime| runtime.borrow().now)
+}
+
+fn collect_defaults(items: &serde_json::Value, defaults: &mut HashMap<String, ValueRef>) {
+	for item in items.as_array().into_iter().flatten() {
+		if let Some(children) = item.get("items") {
+			collect_defaults(children, defaults);
+		}
+		if let (Some(key), Some(value)) = (
+			item.get("key").and_then(|key| key.as_str()),
+			item.get("default"),
+		) {
+			defaults.insert(key.to_string(), ValueRef::from(value.clone()));
+		}
+	}
+}
+
+/// Handle to the runtime of the current test thread.
+pub struct Harness {
+	root: PathBuf,
+}
+
+impl Harness {
+	/// Resets the runtime for a source crate, usually called as
+	/// `Harness::new(env!("CARGO_MANIFEST_DIR"))`.
+	pub fn new<P: AsRef<Path>>(root: P) -> Self {
+		let root = root.as_ref().to_path_buf();
+		let mut runtime = Runtime::default();
+		if let Ok(text) = fs::read_to_string(root.join("res/settings.json")) {
+			let settings = serde_json::from_str::<serde_json::Value>(&text)
+				.unwrap_or_else(|error| panic!("invalid res/settings.json: {}", error));
+			collect_defaults(&settings, &mut runtime.defaults);
+		}
+		RUNTIME.with(|current| *current.borrow_mut() = runtime);
+		Harness { root }
+	}
+
+	/// Adds the routes of `<dir>/fixtures.json`, relative to the crate root.
+	pub fn fixtures<P: AsRef<Path>>(self, dir: P) -> Self {
+		let dir = self.root.join(dir);
+		let manifest = dir.join("fixtures.json");
+		let text = fs::read_to_string(&manifest)
+			.unwrap_or_else(|error| panic!("cannot read {}: {}", manifest.display(), error));
+		let routes = serde_json::from_str::<Vec<Route>>(&text)
+			.unwrap_or_else(|error| panic!("invalid {}: {}", manifest.display(), error));
+		RUNTIME.with(|runtime| {
+			runtime
+				.borrow_mut()
+				.routes
+				.extend(routes.into_iter().map(|route| (route, dir.clone())));
+		});
+		self
+	}
+
+	/// Overrides a setting, as if the user had changed it.
+	pub fn default<V: Into<serde_json::Value>>(self, key: &str, value: V) -> Self {
+		set_default(key, ValueRef::from(value.into()));
+		self
+	}
+
+	/// Fixes the value returned by `current_date`.
+	pub fn date(self, now: f64) -> Self {
+		RUNTIME.with(|runtime| runtime.borrow_mut().now = now);
+		self
+	}
+
+	/// Reads back a setting, including values written by the source.
+	pub fn get_default(&self, key: &str) -> Option<String> {
+		default(key)
+			.and_then(|value| value.as_string().ok())
+			.map(|value| value.read())
+	}
+
+	/// Every request sent since the harness was created.
+	pub fn requests(&self) -> Vec<RecordedRequest> {
+		RUNTIME.with(|runtime| runtime.borrow().requests.clone())
+	}
+
+	/// Asserts that `value` serializes to the content of
+	/// `tests/golden/<name>.json`.
+	pub fn golden<T: Serialize>(&self, name: &str, value: &T) {
+		let path = self
+			.root
+			.join("tests/golden")
+			.join(::std::format!("{}.json", name));
+		let actual = serde_json::to_string_pretty(value).expect("value is not serializable") + "\n";
+		if ::std::env::var_os("UPDATE_GOLDEN").is_some() {
+			fs::create_dir_all(path.parent().unwrap()).unwrap();
+			fs::write(&path, &actual)
+				.unwrap_or_else(|error| panic!("cannot write {}: {}", path.display(), error));
+			return;
+		}
+		let expected = fs::read_to_string(&path).unwrap_or_else(|error| {
+			panic!(
+				"cannot read {}: {} (run with UPDATE_GOLDEN=1 to create it)",
+				path.display(),
+				error
+			)
+		});
+		assert!(
+			expected == actual,
+			"{} is out of date (run with UPDATE_GOLDEN=1 to update it)\n--- expected\n{}\n--- actual\n{}",
+			path.display(),
+			expected,
+			actual
+		);
+	}
+}
+
+/// Builders for the filters the app passes to `get_manga_list`.
+pub mod filter {
+	use super::*;
+
+	fn filter(kind: FilterType, name: &str, value: ValueRef) -> Filter {
+		Filter {
+			kind,
+			name: name.to_string(),
+			value,
+			object: ObjectRef::new(),
+		}
+	}
+
+	pub fn title(query: &str) -> Filter {
+		filter(FilterType::Title, "Title", StringRef::from(query).0)
+	}
+
+	pub fn author(query: &str) -> Filter {
+		filter(FilterType::Author, "Author", StringRef::from(query).0)
+	}
+
+	pub fn text(name: &str, value: &str) -> Filter {
+		filter(FilterType::Text, name, StringRef::from(value).0)
+	}
+
+	pub fn select(name: &str, index: i64) -> Filter {
+		filter(
+			FilterType::Select,
+			name,
+			ValueRef::from(serde_json::json!(index)),
+		)
+	}
+
+	pub fn check(name: &str, checked: bool) -> Filter {
+		filter(
+			FilterType::Check,
+			name,
+			ValueRef::from(serde_json::json!(checked)),
+		)
+	}
+
+	pub fn sort(name: &str, index: i64, ascending: bool) -> Filter {
+		filter(
+			FilterType::Sort,
+			name,
+			ValueRef::from(serde_json::json!({ "index": index, "ascending": ascending })),
+		)
+	}
+}
@@ -0,0 +1,95 @@
+pub mod substring {
+	pub trait Substring {
+		fn substring_before(&self, pat: &str) -> Option<&str>;
+		fn substring_before_last(&self, pat: &str) -> Option<&str>;
+		fn substring_after(&self, pat: &str) -> Option<&str>;
+		fn substring_after_last(&self, pat: &str) -> Option<&str>;
+	}
+
+	impl Substring for str {
+		fn substring_before(&self, pat: &str) -> Option<&str> {
+			self.find(pat).map(|index| &self[..index])
+		}
+
+		fn substring_before_last(&self, pat: &str) -> Option<&str> {
+			self.rfind(pat).map(|index| &self[..index])
+		}
+
+		fn substring_after(&self, pat: &str) -> Option<&str> {
+			self.find(pat).map(|index| &self[index + pat.len()..])
+		}
+
+		fn substring_after_last(&self, pat: &str) -> Option<&str> {
+			self.rfind(pat).map(|index| &self[index + pat.len()..])
+		}
+	}
+}
+
+pub mod uri {
+	const RESERVED: &[u8] = b";,/?:@&=+$-_.!~*'()#";
+
+	fn encode(text: &str, keep: &[u8]) -> String {
+		let mut result = String::new();
+		for byte in text.bytes() {
+			if byte.is_ascii_alphanumeric() || keep.contains(&byte) {
+				result.push(byte as char);
+			} else {
+				result.push_str(&::std::format!("%{:02X}", byte));
+			}
+		}
+		result
+	}
+
+	pub fn encode_uri<T: AsRef<str>>(text: T) -> String {
+		encode(text.as_ref(), RESERVED)
+	}
+
+	pub fn encode_uri_component<T: AsRef<str>>(text: T) -> String {
+		encode(text.as_ref(), b"-_.!~*'()")
+	}
+
+	/// Ordered query string builder; `set` replaces every value of a name.
+	#[derive(Clone, Debug, Default)]
+	pub struct QueryParameters {
+		params: Vec<(String, Option<String>)>,
+	}
+
+	impl QueryParameters {
+		pub fn new() -> Self {
+			QueryParameters::default()
+		}
+
+		pub fn push(&mut self, name: &str, value: Option<&str>) {
+			self.params
+				.push((name.to_string(), value.map(|value| value.to_string())));
+		}
+
+		pub fn set(&mut self, name: &str, value: Option<&str>) {
+			self.remove_all(name);
+			self.push(name, value);
+		}
+
+		pub fn remove_all(&mut self, name: &str) {
+			self.params.retain(|(key, _)| key != name);
+		}
+	}
+
+	impl ::std::fmt::Display for QueryParameters {
+		fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+			let query = self
+				.params
+				.iter()
+				.map(|(name, value)| match value {
+					Some(value) => ::std::format!(
+						"{}={}",
+						encode_uri_component(name),
+						encode_uri_component(value)
+					),
+					None => encode_uri_component(name),
+				})
+				.collect::<Vec<String>>()
+				.join("&");
+			f.write_str(&query)
+		}
+	}
+}
@@ -0,0 +1,18 @@
+//! Native stand-in for the `aidoku` crate.
+//!
+//! Sources are built against this crate instead of the wasm bindings when
+//! their tests run on the host (see `../test.sh`). It mirrors the public API
+//! of the `legacy` branch of aidoku-rs, but every host import is answered
+//! in-process: requests are served from recorded fixtures, HTML is parsed
+//! with `scraper`, JSON with `serde_json`, and defaults live in memory.
+//! The [`harness`] module controls that runtime from a test.
+
+pub mod error;
+pub mod harness;
+pub mod helpers;
+pub mod prelude;
+pub mod std;
+
+mod structs;
+
+pub use structs::*;
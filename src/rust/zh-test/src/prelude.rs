@@ -0,0 +1,21 @@
+pub use crate::{format, println};
+pub use aidoku_macros::*;
+
+#[doc(hidden)]
+pub mod __private {
+	pub use ::std::{format, println};
+}
+
+#[macro_export]
+macro_rules! format {
+	($($arg:tt)*) => {
+		$crate::prelude::__private::format!($($arg)*)
+	};
+}
+
+#[macro_export]
+macro_rules! println {
+	($($arg:tt)*) => {
+		$crate::prelude::__private::println!($($arg)*)
+	};
+}
@@ -0,0 +1,13 @@
+use crate::error::{AidokuError, AidokuErrorKind, Result};
+
+use super::ValueRef;
+
+pub fn defaults_get(key: &str) -> Result<ValueRef> {
+	crate::harness::default(key).ok_or(AidokuError {
+		reason: AidokuErrorKind::DefaultNotFound,
+	})
+}
+
+pub fn defaults_set(key: &str, value: ValueRef) {
+	crate::harness::set_default(key, value);
+}
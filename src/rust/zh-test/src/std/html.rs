@@ -0,0 +1,296 @@
+use ::std::{fmt, rc::Rc};
+
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::error::Result;
+
+use super::{value::Value, ArrayRef, StringRef, ValueRef};
+
+struct Document {
+	html: Html,
+	base_uri: String,
+}
+
+/// A selection of elements in a parsed document, like a jsoup `Elements`.
+#[derive(Clone)]
+pub struct Node {
+	document: Rc<Document>,
+	ids: Vec<NodeId>,
+}
+
+impl fmt::Debug for Node {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Node").field("ids", &self.ids).finish()
+	}
+}
+
+fn normalize(text: &str) -> String {
+	text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn resolve(base: &str, href: &str) -> String {
+	if href.is_empty() || href.contains("://") {
+		return href.to_string();
+	}
+	let scheme_end = base.find("://").map(|index| index + 3).unwrap_or(0);
+	let host_end = base[scheme_end..]
+		.find('/')
+		.map(|index| index + scheme_end)
+		.unwrap_or(base.len());
+	if let Some(rest) = href.strip_prefix("//") {
+		let scheme = &base[..scheme_end];
+		return ::std::format!("{}{}", scheme, rest);
+	}
+	if href.starts_with('/') {
+		return ::std::format!("{}{}", &base[..host_end], href);
+	}
+	let dir_end = base.rfind('/').filter(|index| *index >= host_end);
+	match dir_end {
+		Some(index) => ::std::format!("{}{}", &base[..=index], href),
+		None => ::std::format!("{}/{}", &base[..host_end], href),
+	}
+}
+
+impl Node {
+	pub fn new<T: AsRef<[u8]>>(buf: T) -> Result<Self> {
+		Node::new_with_uri(buf, "")
+	}
+
+	pub fn new_with_uri<T: AsRef<[u8]>, U: AsRef<str>>(buf: T, uri: U) -> Result<Self> {
+		let text = String::from_utf8_lossy(buf.as_ref());
+		let html = Html::parse_document(&text);
+		let root = html.tree.root().id();
+		Ok(Node {
+			document: Rc::new(Document {
+				html,
+				base_uri: uri.as_ref().to_string(),
+			}),
+			ids: vec![root],
+		})
+	}
+
+	pub fn new_fragment<T: AsRef<[u8]>>(buf: T) -> Result<Self> {
+		let text = String::from_utf8_lossy(buf.as_ref());
+		let html = Html::parse_fragment(&text);
+		let root = html.tree.root().id();
+		Ok(Node {
+			document: Rc::new(Document {
+				html,
+				base_uri: String::new(),
+			}),
+			ids: vec![root],
+		})
+	}
+
+	fn with_ids(&self, ids: Vec<NodeId>) -> Node {
+		Node {
+			document: self.document.clone(),
+			ids,
+		}
+	}
+
+	fn elements(&self) -> Vec<ElementRef<'_>> {
+		self.ids
+			.iter()
+			.filter_map(|id| self.document.html.tree.get(*id))
+			.filter_map(ElementRef::wrap)
+			.collect()
+	}
+
+	pub fn select<T: AsRef<str>>(&self, selector: T) -> Node {
+		let selector = match Selector::parse(selector.as_ref()) {
+			Ok(selector) => selector,
+			Err(_) => return self.with_ids(Vec::new()),
+		};
+		let mut ids: Vec<NodeId> = Vec::new();
+		for id in &self.ids {
+			let node = match self.document.html.tree.get(*id) {
+				Some(node) => node,
+				None => continue,
+			};
+			match ElementRef::wrap(node) {
+				Some(element) => {
+					if selector.matches(&element) {
+						ids.push(element.id());
+					}
+					ids.extend(element.select(&selector).map(|found| found.id()));
+				}
+				None => ids.extend(
+					node.descendants()
+						.filter_map(ElementRef::wrap)
+						.filter(|element| selector.matches(element))
+						.map(|element| element.id()),
+				),
+			}
+		}
+		let mut seen: Vec<NodeId> = Vec::new();
+		ids.retain(|id| {
+			if seen.contains(id) {
+				false
+			} else {
+				seen.push(*id);
+				true
+			}
+		});
+		self.with_ids(ids)
+	}
+
+	pub fn attr(&self, attr: &str) -> StringRef {
+		let (name, absolute) = match attr.strip_prefix("abs:") {
+			Some(name) => (name, true),
+			None => (attr, false),
+		};
+		let value = self
+			.elements()
+			.into_iter()
+			.find_map(|element| element.value().attr(name).map(|value| value.to_string()))
+			.unwrap_or_default();
+		if absolute {
+			StringRef::from(resolve(&self.document.base_uri, &value))
+		} else {
+			StringRef::from(value)
+		}
+	}
+
+	pub fn has_attr(&self, attr: &str) -> bool {
+		self.elements()
+			.into_iter()
+			.any(|element| element.value().attr(attr).is_some())
+	}
+
+	pub fn text(&self) -> StringRef {
+		let text = if self.elements().is_empty() && !self.ids.is_empty() {
+			normalize(&self.document.html.root_element().text().collect::<String>())
+		} else {
+			self.elements()
+				.into_iter()
+				.map(|element| normalize(&element.text().collect::<String>()))
+				.filter(|text| !text.is_empty())
+				.collect::<Vec<String>>()
+				.join(" ")
+		};
+		StringRef::from(text)
+	}
+
+	pub fn untrimmed_text(&self) -> StringRef {
+		let text = self
+			.elements()
+			.into_iter()
+			.map(|element| element.text().collect::<String>())
+			.collect::<String>();
+		StringRef::from(text)
+	}
+
+	pub fn own_text(&self) -> StringRef {
+		let text = self
+			.elements()
+			.into_iter()
+			.map(|element| {
+				element
+					.children()
+					.filter_map(|child| child.value().as_text().map(|text| text.to_string()))
+					.collect::<String>()
+			})
+			.map(|text| normalize(&text))
+			.collect::<Vec<String>>()
+			.join(" ");
+		StringRef::from(text)
+	}
+
+	pub fn data(&self) -> StringRef {
+		let text = self
+			.elements()
+			.into_iter()
+			.map(|element| element.text().collect::<String>())
+			.collect::<Vec<String>>()
+			.join("\n");
+		StringRef::from(text)
+	}
+
+	pub fn html(&self) -> StringRef {
+		let html = self
+			.elements()
+			.into_iter()
+			.map(|element| element.inner_html())
+			.collect::<Vec<String>>()
+			.join("\n");
+		StringRef::from(html)
+	}
+
+	pub fn outer_html(&self) -> StringRef {
+		let html = self
+			.elements()
+			.into_iter()
+			.map(|element| element.html())
+			.collect::<Vec<String>>()
+			.join("\n");
+		StringRef::from(html)
+	}
+
+	pub fn id(&self) -> StringRef {
+		self.attr("id")
+	}
+
+	pub fn tag_name(&self) -> StringRef {
+		let name = self
+			.elements()
+			.first()
+			.map(|element| element.value().name().to_string())
+			.unwrap_or_default();
+		StringRef::from(name)
+	}
+
+	pub fn class_name(&self) -> StringRef {
+		self.attr("class")
+	}
+
+	pub fn array(&self) -> ArrayRef {
+		let nodes = self
+			.elements()
+			.into_iter()
+			.map(|element| ValueRef(Value::Node(self.with_ids(vec![element.id()]))))
+			.collect::<Vec<ValueRef>>();
+		ArrayRef::from_values(nodes)
+	}
+
+	pub fn first(&self) -> Node {
+		let ids = self.ids.first().map(|id| vec![*id]).unwrap_or_default();
+		self.with_ids(ids)
+	}
+
+	pub fn last(&self) -> Node {
+		let ids = self.ids.last().map(|id| vec![*id]).unwrap_or_default();
+		self.with_ids(ids)
+	}
+
+	pub fn parent(&self) -> Node {
+		let ids = self
+			.elements()
+			.first()
+			.and_then(|element| element.parent())
+			.map(|parent| vec![parent.id()])
+			.unwrap_or_default();
+		self.with_ids(ids)
+	}
+
+	pub fn next(&self) -> Node {
+		let ids = self
+			.elements()
+			.first()
+			.and_then(|element| element.next_siblings().find_map(ElementRef::wrap))
+			.map(|sibling| vec![sibling.id()])
+			.unwrap_or_default();
+		self.with_ids(ids)
+	}
+
+	pub fn prev(&self) -> Node {
+		let ids = self
+			.elements()
+			.first()
+			.and_then(|element| element.prev_siblings().find_map(ElementRef::wrap))
+			.map(|sibling| vec![sibling.id()])
+			.unwrap_or_default();
+		self.with_ids(ids)
+	}
+}
@@ -0,0 +1,11 @@
+use crate::error::{AidokuError, AidokuErrorKind, Result};
+
+use super::ValueRef;
+
+pub fn parse<T: AsRef<[u8]>>(buf: T) -> Result<ValueRef> {
+	serde_json::from_slice::<serde_json::Value>(buf.as_ref())
+		.map(ValueRef::from)
+		.map_err(|_| AidokuError {
+			reason: AidokuErrorKind::JsonParseError,
+		})
+}
@@ -0,0 +1,18 @@
+pub use ::std::{string::String, vec::Vec};
+
+pub mod defaults;
+pub mod html;
+pub mod json;
+pub mod net;
+
+mod value;
+
+pub use value::{ArrayRef, Kind, ObjectRef, StringRef, ValueRef};
+
+pub fn current_date() -> f64 {
+	crate::harness::now()
+}
+
+pub fn print<T: AsRef<str>>(text: T) {
+	::std::println!("{}", text.as_ref());
+}
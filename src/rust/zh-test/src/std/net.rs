@@ -0,0 +1,122 @@
+use ::std::cell::RefCell;
+
+use crate::{
+	error::{AidokuError, AidokuErrorKind, Result},
+	harness::{self, Response},
+};
+
+use super::{html::Node, json, StringRef, ValueRef};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpMethod {
+	Get,
+	Post,
+	Head,
+	Put,
+	Delete,
+}
+
+#[derive(Debug)]
+struct State {
+	method: HttpMethod,
+	url: String,
+	headers: Vec<(String, String)>,
+	body: Option<Vec<u8>>,
+	response: Option<Response>,
+}
+
+/// A request that is answered by the fixtures loaded into the harness.
+///
+/// Like the host, a request is sent at most once: the first call to
+/// [`Request::send`] or to one of the reading methods fixes the response.
+#[derive(Debug)]
+pub struct Request(RefCell<State>);
+
+impl Request {
+	pub fn new<T: AsRef<str>>(url: T, method: HttpMethod) -> Self {
+		Request(RefCell::new(State {
+			method,
+			url: url.as_ref().to_string(),
+			headers: Vec::new(),
+			body: None,
+			response: None,
+		}))
+	}
+
+	pub fn get<T: AsRef<str>>(url: T) -> Self {
+		Request::new(url, HttpMethod::Get)
+	}
+
+	pub fn post<T: AsRef<str>>(url: T) -> Self {
+		Request::new(url, HttpMethod::Post)
+	}
+
+	pub fn header(self, key: &str, val: &str) -> Self {
+		{
+			let mut state = self.0.borrow_mut();
+			state
+				.headers
+				.retain(|(name, _)| !name.eq_ignore_ascii_case(key));
+			state.headers.push((key.to_string(), val.to_string()));
+		}
+		self
+	}
+
+	pub fn body<T: AsRef<[u8]>>(self, data: T) -> Self {
+		self.0.borrow_mut().body = Some(data.as_ref().to_vec());
+		self
+	}
+
+	pub fn url(&self) -> StringRef {
+		StringRef::from(self.0.borrow().url.clone())
+	}
+
+	pub fn send(&self) {
+		let mut state = self.0.borrow_mut();
+		if state.response.is_none() {
+			let response = harness::respond(
+				state.method,
+				&state.url,
+				&state.headers,
+				state.body.as_deref(),
+			);
+			state.response = Some(response);
+		}
+	}
+
+	fn response(&self) -> Response {
+		self.send();
+		self.0.borrow().response.clone().unwrap_or_default()
+	}
+
+	pub fn status_code(&self) -> i32 {
+		self.response().status
+	}
+
+	pub fn get_header(&self, header: &str) -> Option<StringRef> {
+		self.response()
+			.headers
+			.into_iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(header))
+			.map(|(_, value)| StringRef::from(value))
+	}
+
+	pub fn data(self) -> Vec<u8> {
+		self.response().body
+	}
+
+	pub fn string(self) -> Result<String> {
+		String::from_utf8(self.data()).map_err(|_| AidokuError {
+			reason: AidokuErrorKind::Unimplemented,
+		})
+	}
+
+	pub fn json(self) -> Result<ValueRef> {
+		json::parse(self.data())
+	}
+
+	pub fn html(self) -> Result<Node> {
+		let url = self.0.borrow().url.clone();
+		Node::new_with_uri(self.data(), url)
+	}
+}
@@ -0,0 +1,327 @@
+use crate::error::{Result, ValueCastError};
+
+use super::html::Node;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+	Null = 0,
+	Int = 1,
+	Float = 2,
+	String = 3,
+	Bool = 4,
+	Array = 5,
+	Object = 6,
+	Date = 7,
+	Node = 8,
+	Unknown = 9,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Value {
+	Null,
+	Int(i64),
+	Float(f64),
+	String(String),
+	Bool(bool),
+	Array(Vec<ValueRef>),
+	Object(Vec<(String, ValueRef)>),
+	Node(Node),
+}
+
+/// A value owned by the host. Natively the value is held inline.
+#[derive(Clone, Debug)]
+pub struct ValueRef(pub(crate) Value);
+
+impl ValueRef {
+	pub(crate) fn null() -> Self {
+		ValueRef(Value::Null)
+	}
+
+	pub fn kind(&self) -> Kind {
+		match self.0 {
+			Value::Null => Kind::Null,
+			Value::Int(_) => Kind::Int,
+			Value::Float(_) => Kind::Float,
+			Value::String(_) => Kind::String,
+			Value::Bool(_) => Kind::Bool,
+			Value::Array(_) => Kind::Array,
+			Value::Object(_) => Kind::Object,
+			Value::Node(_) => Kind::Node,
+		}
+	}
+
+	pub fn is_none(&self) -> bool {
+		matches!(self.0, Value::Null)
+	}
+
+	pub fn as_string(&self) -> Result<StringRef> {
+		match self.0 {
+			Value::String(_) => Ok(StringRef(self.clone())),
+			_ => Err(ValueCastError::NotString.into()),
+		}
+	}
+
+	pub fn as_object(&self) -> Result<ObjectRef> {
+		match self.0 {
+			Value::Object(_) => Ok(ObjectRef(self.clone())),
+			_ => Err(ValueCastError::NotObject.into()),
+		}
+	}
+
+	pub fn as_array(&self) -> Result<ArrayRef> {
+		match &self.0 {
+			Value::Array(items) => Ok(ArrayRef(self.clone(), 0, items.len())),
+			_ => Err(ValueCastError::NotArray.into()),
+		}
+	}
+
+	pub fn as_int(&self) -> Result<i64> {
+		match &self.0 {
+			Value::Int(value) => Ok(*value),
+			Value::Float(value) => Ok(*value as i64),
+			Value::Bool(value) => Ok(*value as i64),
+			Value::String(value) => value
+				.trim()
+				.parse::<i64>()
+				.map_err(|_| ValueCastError::NotNumber.into()),
+			_ => Err(ValueCastError::NotNumber.into()),
+		}
+	}
+
+	pub fn as_float(&self) -> Result<f64> {
+		match &self.0 {
+			Value::Int(value) => Ok(*value as f64),
+			Value::Float(value) => Ok(*value),
+			Value::String(value) => value
+				.trim()
+				.parse::<f64>()
+				.map_err(|_| ValueCastError::NotNumber.into()),
+			_ => Err(ValueCastError::NotNumber.into()),
+		}
+	}
+
+	pub fn as_bool(&self) -> Result<bool> {
+		match &self.0 {
+			Value::Bool(value) => Ok(*value),
+			Value::Int(value) => Ok(*value != 0),
+			_ => Err(ValueCastError::NotBool.into()),
+		}
+	}
+
+	pub fn as_node(&self) -> Result<Node> {
+		match &self.0 {
+			Value::Node(node) => Ok(node.clone()),
+			_ => Err(ValueCastError::NotNode.into()),
+		}
+	}
+
+	pub fn as_date(
+		&self,
+		_format: &str,
+		_locale: Option<&str>,
+		_timezone: Option<&str>,
+	) -> Result<f64> {
+		self.as_float()
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct StringRef(pub ValueRef);
+
+impl StringRef {
+	pub fn read(&self) -> String {
+		match &self.0 .0 {
+			Value::String(value) => value.clone(),
+			_ => String::new(),
+		}
+	}
+}
+
+impl ::std::fmt::Display for StringRef {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+		f.write_str(&self.read())
+	}
+}
+
+impl Default for StringRef {
+	fn default() -> Self {
+		StringRef(ValueRef(Value::String(String::new())))
+	}
+}
+
+impl From<&str> for StringRef {
+	fn from(value: &str) -> Self {
+		StringRef(ValueRef(Value::String(value.to_string())))
+	}
+}
+
+impl From<String> for StringRef {
+	fn from(value: String) -> Self {
+		StringRef(ValueRef(Value::String(value)))
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectRef(pub ValueRef);
+
+impl ObjectRef {
+	pub fn new() -> Self {
+		ObjectRef(ValueRef(Value::Object(Vec::new())))
+	}
+
+	fn entries(&self) -> &[(String, ValueRef)] {
+		match &self.0 .0 {
+			Value::Object(entries) => entries,
+			_ => &[],
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries().len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries().is_empty()
+	}
+
+	pub fn get(&self, key: &str) -> ValueRef {
+		self.entries()
+			.iter()
+			.find(|(name, _)| name == key)
+			.map(|(_, value)| value.clone())
+			.unwrap_or_else(ValueRef::null)
+	}
+
+	pub fn set(&mut self, key: &str, value: ValueRef) {
+		if let Value::Object(entries) = &mut self.0 .0 {
+			match entries.iter_mut().find(|(name, _)| name == key) {
+				Some(entry) => entry.1 = value,
+				None => entries.push((key.to_string(), value)),
+			}
+		}
+	}
+
+	pub fn remove(&mut self, key: &str) {
+		if let Value::Object(entries) = &mut self.0 .0 {
+			entries.retain(|(name, _)| name != key);
+		}
+	}
+
+	pub fn keys(&self) -> ArrayRef {
+		let keys = self
+			.entries()
+			.iter()
+			.map(|(name, _)| ValueRef(Value::String(name.clone())))
+			.collect::<Vec<ValueRef>>();
+		ArrayRef::from_values(keys)
+	}
+
+	pub fn values(&self) -> ArrayRef {
+		let values = self
+			.entries()
+			.iter()
+			.map(|(_, value)| value.clone())
+			.collect::<Vec<ValueRef>>();
+		ArrayRef::from_values(values)
+	}
+}
+
+impl Default for ObjectRef {
+	fn default() -> Self {
+		ObjectRef::new()
+	}
+}
+
+/// An array handle that is also an iterator over its remaining items.
+#[derive(Clone, Debug)]
+pub struct ArrayRef(pub ValueRef, usize, usize);
+
+impl ArrayRef {
+	pub fn new() -> Self {
+		ArrayRef::from_values(Vec::new())
+	}
+
+	pub(crate) fn from_values(values: Vec<ValueRef>) -> Self {
+		let len = values.len();
+		ArrayRef(ValueRef(Value::Array(values)), 0, len)
+	}
+
+	fn items(&self) -> &[ValueRef] {
+		match &self.0 .0 {
+			Value::Array(items) => items,
+			_ => &[],
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.items().len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.items().is_empty()
+	}
+
+	pub fn get(&self, index: usize) -> ValueRef {
+		self.items()
+			.get(index)
+			.cloned()
+			.unwrap_or_else(ValueRef::null)
+	}
+
+	pub fn insert(&mut self, value: ValueRef) {
+		if let Value::Array(items) = &mut self.0 .0 {
+			items.push(value);
+			self.2 = items.len();
+		}
+	}
+
+	pub fn remove(&mut self, index: usize) {
+		if let Value::Array(items) = &mut self.0 .0 {
+			if index < items.len() {
+				items.remove(index);
+				self.2 = items.len();
+			}
+		}
+	}
+}
+
+impl Default for ArrayRef {
+	fn default() -> Self {
+		ArrayRef::new()
+	}
+}
+
+impl Iterator for ArrayRef {
+	type Item = ValueRef;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.1 >= self.2 {
+			return None;
+		}
+		let value = self.get(self.1);
+		self.1 += 1;
+		Some(value)
+	}
+}
+
+impl From<serde_json::Value> for ValueRef {
+	fn from(value: serde_json::Value) -> Self {
+		use serde_json::Value as Json;
+		ValueRef(match value {
+			Json::Null => Value::Null,
+			Json::Bool(value) => Value::Bool(value),
+			Json::Number(number) => match number.as_i64() {
+				Some(value) => Value::Int(value),
+				None => Value::Float(number.as_f64().unwrap_or_default()),
+			},
+			Json::String(value) => Value::String(value),
+			Json::Array(items) => Value::Array(items.into_iter().map(ValueRef::from).collect()),
+			Json::Object(entries) => Value::Object(
+				entries
+					.into_iter()
+					.map(|(key, value)| (key, ValueRef::from(value)))
+					.collect(),
+			),
+		})
+	}
+}
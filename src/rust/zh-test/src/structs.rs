@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::std::{ObjectRef, ValueRef};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MangaStatus {
+	#[default]
+	Unknown = 0,
+	Ongoing = 1,
+	Completed = 2,
+	Cancelled = 3,
+	Hiatus = 4,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MangaContentRating {
+	#[default]
+	Safe = 0,
+	Suggestive = 1,
+	Nsfw = 2,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MangaViewer {
+	#[default]
+	Default = 0,
+	Rtl = 1,
+	Ltr = 2,
+	Vertical = 3,
+	Scroll = 4,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manga {
+	pub id: String,
+	pub cover: String,
+	pub title: String,
+	pub author: String,
+	pub artist: String,
+	pub description: String,
+	pub url: String,
+	pub categories: Vec<String>,
+	pub status: MangaStatus,
+	pub nsfw: MangaContentRating,
+	pub viewer: MangaViewer,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MangaPageResult {
+	pub manga: Vec<Manga>,
+	pub has_more: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+	pub id: String,
+	pub title: String,
+	pub volume: f32,
+	pub chapter: f32,
+	pub date_updated: f64,
+	pub scanlator: String,
+	pub url: String,
+	pub lang: String,
+}
+
+impl Default for Chapter {
+	fn default() -> Self {
+		Chapter {
+			id: String::new(),
+			title: String::new(),
+			volume: -1.0,
+			chapter: -1.0,
+			date_updated: -1.0,
+			scanlator: String::new(),
+			url: String::new(),
+			lang: String::from("en"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+	pub index: i32,
+	pub url: String,
+	pub base64: String,
+	pub text: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeepLink {
+	pub manga: Option<Manga>,
+	pub chapter: Option<Chapter>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Listing {
+	pub name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterType {
+	Base = 0,
+	Group = 1,
+	Text = 2,
+	Check = 3,
+	Select = 4,
+	Sort = 5,
+	SortSelection = 6,
+	Title = 7,
+	Author = 8,
+	Genre = 9,
+}
+
+#[derive(Clone, Debug)]
+pub struct Filter {
+	pub kind: FilterType,
+	pub name: String,
+	pub value: ValueRef,
+	pub object: ObjectRef,
+}
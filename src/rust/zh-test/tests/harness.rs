@@ -0,0 +1,149 @@
+use aidoku::{
+	harness::{filter, Harness},
+	std::{
+		defaults::{defaults_get, defaults_set},
+		net::{HttpMethod, Request},
+		StringRef,
+	},
+	FilterType,
+};
+
+fn harness() -> Harness {
+	Harness::new(env!("CARGO_MANIFEST_DIR")).fixtures("tests/fixtures/harness")
+}
+
+#[test]
+fn serves_html_fixtures() {
+	let _harness = harness();
+	let html = Request::new("https://example.com/list?page=1", HttpMethod::Get)
+		.header("User-Agent", "test")
+		.html()
+		.unwrap();
+
+	assert_eq!(html.select("title").text().read(), "List");
+	let items = html.select(".list>.item").array().collect::<Vec<_>>();
+	assert_eq!(items.len(), 2);
+	let item = items[1].as_node().unwrap();
+	assert_eq!(item.select("a").attr("title").read(), "Two");
+	assert_eq!(
+		item.select("a").attr("abs:href").read(),
+		"https://example.com/comic/2.html"
+	);
+	assert_eq!(item.select("span").text().read(), "第2话");
+	assert_eq!(html.select(".item a").attr("href").read(), "/comic/1.html");
+	assert!(html.select(".missing").array().next().is_none());
+}
+
+#[test]
+fn serves_json_fixtures_by_body() {
+	let harness = harness();
+	let request = Request::new("https://example.com/api", HttpMethod::Post)
+		.header("Content-Type", "application/json")
+		.body(r#"{"id":"1"}"#.as_bytes());
+	request.send();
+	assert_eq!(request.status_code(), 200);
+	assert_eq!(
+		request.get_header("set-cookie").map(|value| value.read()),
+		Some(String::from("session=abc"))
+	);
+
+	let data = request
+		.json()
+		.unwrap()
+		.as_object()
+		.unwrap()
+		.get("data")
+		.as_object()
+		.unwrap();
+	assert_eq!(data.get("title").as_string().unwrap().read(), "One");
+	assert_eq!(data.get("score").as_float().unwrap(), 9.5);
+	assert!(data.get("finished").as_bool().unwrap());
+	let tags = data
+		.get("tags")
+		.as_array()
+		.unwrap()
+		.map(|tag| tag.as_string().unwrap().read())
+		.collect::<Vec<String>>();
+	assert_eq!(tags, ["a", "b"]);
+
+	let requests = harness.requests();
+	assert_eq!(requests.len(), 1);
+	assert_eq!(requests[0].header("content-type"), Some("application/json"));
+	assert_eq!(requests[0].body_string(), r#"{"id":"1"}"#);
+}
+
+#[test]
+fn patches_json_fixtures() {
+	let _harness = harness();
+	let data = Request::new("https://example.com/api", HttpMethod::Post)
+		.body(r#"{"id":"2"}"#.as_bytes())
+		.json()
+		.unwrap()
+		.as_object()
+		.unwrap()
+		.get("data")
+		.as_object()
+		.unwrap();
+	assert_eq!(data.get("title").as_string().unwrap().read(), "Two");
+	assert!(data.get("score").as_float().is_err());
+	assert!(data.get("finished").as_bool().unwrap());
+	let tags = data
+		.get("tags")
+		.as_array()
+		.unwrap()
+		.map(|tag| tag.as_string().unwrap().read())
+		.collect::<Vec<String>>();
+	assert_eq!(tags, ["c"]);
+}
+
+#[test]
+fn reports_status_codes() {
+	let _harness = harness();
+	let request = Request::new("https://example.com/missing", HttpMethod::Get);
+	assert_eq!(request.status_code(), 404);
+}
+
+#[test]
+fn matches_request_headers() {
+	let _harness = harness();
+	let status = |authorization: &str| {
+		Request::new("https://example.com/account", HttpMethod::Get)
+			.header("authorization", authorization)
+			.status_code()
+	};
+	assert_eq!(status("revoked"), 401);
+	assert_eq!(status("valid"), 200);
+}
+
+#[test]
+#[should_panic(expected = "no fixture for GET https://example.com/unknown")]
+fn panics_without_fixture() {
+	let _harness = harness();
+	Request::new("https://example.com/unknown", HttpMethod::Get).send();
+}
+
+#[test]
+fn stores_defaults_in_memory() {
+	let harness = Harness::new(env!("CARGO_MANIFEST_DIR")).default("token", "abc");
+	assert_eq!(
+		defaults_get("token").unwrap().as_string().unwrap().read(),
+		"abc"
+	);
+	assert!(defaults_get("missing").is_err());
+
+	defaults_set("token", StringRef::from("def").0);
+	assert_eq!(harness.get_default("token"), Some(String::from("def")));
+}
+
+#[test]
+fn builds_filters() {
+	let sort = filter::sort("排序", 2, true);
+	assert_eq!(sort.kind, FilterType::Sort);
+	let value = sort.value.as_object().unwrap();
+	assert_eq!(value.get("index").as_int().unwrap(), 2);
+	assert!(value.get("ascending").as_bool().unwrap());
+
+	let select = filter::select("类型", 3);
+	assert_eq!(select.kind, FilterType::Select);
+	assert_eq!(select.value.as_int().unwrap(), 3);
+}
@@ -13,6 +13,7 @@ use aidoku::{
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::filter::{select_value, sort_value};
 
 const WWW_URL: &str = "https://139mh.com";
 const API_URL: &str = "https://api.139mh.com";
@@ -95,24 +96,23 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
 					"分类" => {
-						subject = FILTER_SUBJECT[index].to_string();
+						subject = select_value(&FILTER_SUBJECT, index);
 					}
 					"地区" => {
-						area = FILTER_AREA[index].to_string();
+						area = select_value(&FILTER_AREA, index);
 					}
 					"状态" => {
-						progress = FILTER_PROGRESS[index].to_string();
+						progress = select_value(&FILTER_PROGRESS, index);
 					}
 					_ => continue,
 				}
 			}
 			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
+				let (index, _) = match sort_value(&filter) {
+					Some(value) => value,
+					None => continue,
 				};
-				let index = value.get("index").as_int()? as usize;
-				order = FILTER_ORDER[index].to_string();
+				order = select_value(&FILTER_ORDER, index);
 			}
 			_ => continue,
 		}
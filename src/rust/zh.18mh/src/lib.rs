@@ -9,57 +9,28 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter::find_chapter,
+	filter::FilterValues,
+	id::{join_pair, last_segment, split_pair},
+	link::unsupported,
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://18mh.org";
 
+const FILTERS: &str = include_str!("../res/filters.json");
 const FILTER_GENRE: [&str; 4] = ["hanman", "zhenrenxiezhen", "riman", "aixiezhen"];
-const FILTER_CATEGORY: [&str; 18] = [
-	"",
-	"hanman",
-	"zhenrenxiezhen",
-	"riman",
-	"aixiezhen",
-	"duoren",
-	"yuwang",
-	"zhengmei",
-	"tongju",
-	"nxuesheng",
-	"juqing",
-	"touqing",
-	"xiaoyuan",
-	"nixi",
-	"bangongshi",
-	"youhuo",
-	"fanzhuan",
-	"shun",
-];
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let category = filters.get("类型");
 
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
@@ -84,14 +55,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let cover = item.select("div>img").attr("src").read();
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
@@ -135,14 +99,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let cover = item.select("div>img").attr("src").read();
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
@@ -161,8 +118,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/manga/{}", WWW_URL, ids[0]);
+	let (slug, _) = split_pair(&id);
+	let url = format!("{}/manga/{}", WWW_URL, slug);
 	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
 	let mid = html.select("#mangachapters").attr("data-mid").read();
 	let cover = html
@@ -205,7 +162,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let viewer = MangaViewer::Scroll;
 
 	Ok(Manga {
-		id: format!("{}/{}", ids[0], mid),
+		id: join_pair(&slug, &mid),
 		cover,
 		title,
 		author,
@@ -221,8 +178,8 @@ fn get_manga_details(id: String) -> Result<Manga> {
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/manga/get?mid={}&mode=all", WWW_URL, ids[1]);
+	let (_, mid) = split_pair(&id);
+	let url = format!("{}/manga/get?mid={}&mode=all", WWW_URL, mid);
 	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
 	let list = html.select("#allchapterlist>.chapteritem>a").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
@@ -257,11 +214,11 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
-	let ids = manga_id.split("/").collect::<Vec<&str>>();
+	let (_, mid) = split_pair(&manga_id);
 	let url = format!(
 		"{}/chapter/getcontent?m={}&c={}",
 		WWW_URL,
-		ids[1],
+		mid,
 		chapter_id.clone()
 	);
 	let html = Request::new(url.clone(), HttpMethod::Get)
@@ -295,3 +252,24 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", &WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = path_segments(&url);
+	let slug = match segments.as_slice() {
+		[kind, slug, ..] if kind == "manga" => slug.clone(),
+		_ => return Err(unsupported()),
+	};
+	let manga = get_manga_details(slug)?;
+	// Chapter IDs are not part of the chapter URL.
+	let chapter = if segments.len() > 2 {
+		find_chapter(get_chapter_list(manga.id.clone())?, &url)
+	} else {
+		None
+	};
+
+	Ok(DeepLink {
+		manga: Some(manga),
+		chapter,
+	})
+}
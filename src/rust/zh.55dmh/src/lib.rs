@@ -13,6 +13,10 @@ use aidoku::{
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::{select_value, sort_value},
+	id::{html_slug, last_segment},
+};
 
 const WWW_URL: &str = "https://www.55dmh.com";
 
@@ -50,25 +54,23 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
 					"题材" => {
-						category = FILTER_CATEGORY[index].to_string();
+						category = select_value(&FILTER_CATEGORY, index);
 					}
 					"读者" => {
-						audience = FILTER_AUDIENCE[index].to_string();
+						audience = select_value(&FILTER_AUDIENCE, index);
 					}
 					"进度" => {
-						status = FILTER_STATUS[index].to_string();
+						status = select_value(&FILTER_STATUS, index);
 					}
 					_ => continue,
 				}
 			}
 			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
+				let (index, ascending) = match sort_value(&filter) {
+					Some(value) => value,
+					None => continue,
 				};
-				let index = value.get("index").as_int()? as usize;
-				let ascending = value.get("ascending").as_bool().unwrap_or(false);
-				sort = FILTER_SORT[index].to_string();
+				sort = select_value(&FILTER_SORT, index);
 				if ascending {
 					sort = format!("-{}", sort)
 				}
@@ -99,16 +101,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".cover")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select(".cover").attr("href").read());
 		let cover = item.select(".cover>img").attr("src").read();
 		let title = item.select("dl>dt>a").text().read().trim().to_string();
 		mangas.push(Manga {
@@ -146,15 +139,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let cover = item.attr("i").read();
 		let title = item.text().read().trim().to_string();
 		mangas.push(Manga {
@@ -238,15 +223,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = html_slug(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
 		let chapter = (len - index) as f32;
 		let url = format!(
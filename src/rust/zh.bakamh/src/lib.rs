@@ -9,37 +9,26 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://bakamh.com";
 
-const FILTER_FINISH: [&str; 3] = ["", "on-going", "end"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut finish = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"进度" => {
-						finish = FILTER_FINISH[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let finish = filters.get("进度");
 
 	let url = if query.is_empty() {
 		format!("{}/{}/page/{}/", WWW_URL, finish, page)
@@ -61,16 +50,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".item-thumb>a")
-				.attr("href")
-				.read()
-				.split("/")
-				.map(|a| a.to_string())
-				.filter(|a| !a.is_empty())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = last_segment(item.select(".item-thumb>a").attr("href").read());
 			let cover = item
 				.select(".item-thumb>a>img")
 				.attr("src")
@@ -90,16 +70,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".col-4>.tab-thumb>a")
-				.attr("href")
-				.read()
-				.split("/")
-				.map(|a| a.to_string())
-				.filter(|a| !a.is_empty())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = last_segment(item.select(".col-4>.tab-thumb>a").attr("href").read());
 			let cover = item
 				.select(".col-4>.tab-thumb>a>img")
 				.attr("src")
@@ -145,16 +116,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".item-thumb>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select(".item-thumb>a").attr("href").read());
 		let cover = item
 			.select(".item-thumb>a>img")
 			.attr("src")
@@ -248,13 +210,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Err(_) => continue,
 		};
 		let url = item.attr("href").read();
-		let id = url
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(&url);
 		let title = item.text().read().trim().to_string();
 		let chapter = (len - index) as f32;
 		chapters.push(Chapter {
@@ -297,6 +253,17 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	Ok(pages)
 }
 
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if kind == "manga" => (id.clone(), None),
+		[kind, id, chapter_id] if kind == "manga" => (id.clone(), Some(chapter_id.clone())),
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
+
 #[modify_image_request]
 fn modify_image_request(request: Request) {
 	request.header("Referer", WWW_URL);
@@ -9,75 +9,29 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::{html_slug, last_segment},
+	link::{deep_link, unsupported},
+	url::{path_segments, query_value},
+};
 
 const WWW_URL: &str = "https://www.baozimh.com";
 const IMG_URL: &str = "https://static-tw.baozimh.com";
 
-const FILTER_CATEGORY: [&str; 26] = [
-	"all",
-	"lianai",
-	"chunai",
-	"gufeng",
-	"yineng",
-	"xuanyi",
-	"juqing",
-	"kehuan",
-	"qihuan",
-	"xuanhuan",
-	"chuanyue",
-	"maoxian",
-	"tuili",
-	"wuxia",
-	"gedou",
-	"zhanzheng",
-	"rexie",
-	"gaoxiao",
-	"danuzhu",
-	"dushi",
-	"zongcai",
-	"hougong",
-	"richang",
-	"hanman",
-	"shaonian",
-	"qita",
-];
-const FILTER_REGION: [&str; 5] = ["all", "cn", "jp", "kr", "en"];
-const FILTER_STATUS: [&str; 3] = ["all", "serial", "pub"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-	let mut region = String::new();
-	let mut status = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					"地区" => {
-						region = FILTER_REGION[index].to_string();
-					}
-					"状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let category = filters.get("类型");
+	let region = filters.get("地区");
+	let status = filters.get("状态");
 
 	let has_more = query.is_empty();
 	let mut mangas: Vec<Manga> = Vec::new();
@@ -120,16 +74,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".comics-card__info")
-				.attr("href")
-				.read()
-				.split("/")
-				.filter(|a| !a.is_empty())
-				.map(|a| a.to_string())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = last_segment(item.select(".comics-card__info").attr("href").read());
 			let cover = item
 				.select(".comics-card__poster>amp-img")
 				.attr("src")
@@ -159,16 +104,11 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/{}", WWW_URL, id.clone());
 	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
-	let id = html
-		.select("meta[name='og:novel:read_url']")
-		.attr("content")
-		.read()
-		.split("/")
-		.filter(|a| !a.is_empty())
-		.map(|a| a.to_string())
-		.collect::<Vec<String>>()
-		.pop()
-		.unwrap();
+	let id = last_segment(
+		html.select("meta[name='og:novel:read_url']")
+			.attr("content")
+			.read(),
+	);
 	let cover = html.select("meta[name='og:image'").attr("content").read();
 	let title = html
 		.select("meta[name='og:novel:book_name']")
@@ -280,16 +220,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		);
 		let html = Request::new(url.clone(), HttpMethod::Get).html()?;
 		let list = html.select("amp-img[id^='chapter-img']").array();
-		let next_chapter_id = html
-			.select("#next-chapter")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let next_chapter_id = html_slug(html.select("#next-chapter").attr("href").read());
 
 		for item in list {
 			let item = match item.as_node() {
@@ -317,3 +248,28 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		// Later pages of a chapter are `<section>_<chapter>_<page>.html`.
+		[kind, chapter, id, slug] if kind == "comic" && chapter == "chapter" => {
+			let chapter_id = html_slug(slug)
+				.split('_')
+				.take(2)
+				.collect::<Vec<&str>>()
+				.join("_");
+			(id.clone(), Some(chapter_id))
+		}
+		[user, kind] if user == "user" && kind == "page_direct" => {
+			let id = query_value(&url, "comic_id").ok_or_else(unsupported)?;
+			let section = query_value(&url, "section_slot").ok_or_else(unsupported)?;
+			let chapter = query_value(&url, "chapter_slot").ok_or_else(unsupported)?;
+			(id, Some(format!("{}_{}", section, chapter)))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
@@ -9,136 +9,44 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
-use regex::Regex;
+use zh_common::{
+	chapter::extract_chapter_number,
+	filter::FilterValues,
+	id::html_slug,
+	link::{deep_link, unsupported},
+	request::{Profile, MOBILE_UA},
+	url::path_segments,
+};
 
 const BASE_URL: &str = "https://www.bilimanga.net";
-const UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1";
-
-const FILTER_TAGID: [&str; 66] = [
-	"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
-	"17", "18", "19", "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32",
-	"33", "34", "35", "36", "37", "38", "39", "40", "41", "42", "43", "44", "45", "46", "47", "48",
-	"49", "50", "51", "52", "53", "54", "55", "56", "57", "58", "59", "60", "61", "62", "63", "64",
-	"65",
-];
-const FILTER_SORTID: [&str; 13] = [
-	"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12",
-];
-const FILTER_RGROUPID: [&str; 6] = ["0", "1", "2", "3", "4", "5"];
-const FILTER_ORDER: [&str; 10] = [
-	"weekvisit",
-	"monthvisit",
-	"weekvote",
-	"monthvote",
-	"weekflower",
-	"monthflower",
-	"words",
-	"goodnum",
-	"lastupdate",
-	"postdate",
-];
-const FILTER_ANIME: [&str; 3] = ["0", "1", "2"];
-const FILTER_QUALITY: [&str; 3] = ["0", "1", "2"];
-const FILTER_ISFULL: [&str; 3] = ["0", "1", "2"];
-const FILTER_UPDATE: [&str; 5] = ["0", "1", "2", "3", "4"];
 
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method)
-		.header("Origin", BASE_URL)
-		.header("User-Agent", UA)
-		.header("Accept-Language", "zh-CN,zh;q=0.9")
-		.header("Cookie", "night=0")
-}
+const FILTERS: &str = include_str!("../res/filters.json");
 
-fn extract_chapter_number(title: &str) -> Option<f32> {
-	let re = Regex::new(r"(?:第\s*)([\d０-９]+(?:\.[\d０-９]+)?)|([\d０-９]+(?:\.[\d０-９]+)?)\s*(?:话|話|章|回|卷|册|冊)").unwrap();
-	if let Some(captures) = re.captures(title) {
-		let num_match = captures.get(1).or_else(|| captures.get(2));
-		if let Some(num_match) = num_match {
-			let num_str = num_match.as_str()
-				.chars()
-				.map(|c| match c {
-					'０' => '0',
-					'１' => '1',
-					'２' => '2',
-					'３' => '3',
-					'４' => '4',
-					'５' => '5',
-					'６' => '6',
-					'７' => '7',
-					'８' => '8',
-					'９' => '9',
-					'．' => '.',
-					other => other,
-				})
-				.collect::<String>();
-			if let Ok(num) = num_str.parse::<f32>() {
-				return Some(num);
-			}
-		}
-	}
-	None
-}
+const PROFILE: Profile = Profile {
+	user_agent: MOBILE_UA,
+	headers: &[
+		("Origin", BASE_URL),
+		("Accept-Language", "zh-CN,zh;q=0.9"),
+		("Cookie", "night=0"),
+	],
+};
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut tagid = String::from("0");
-	let mut sortid = String::from("0");
-	let mut rgroupid = String::from("0");
-	let mut order = String::from("lastupdate");
-	let mut anime = String::from("0");
-	let mut quality = String::from("0");
-	let mut isfull = String::from("0");
-	let mut update = String::from("0");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"作品主题" => {
-						tagid = FILTER_TAGID[index].to_string();
-					}
-					"作品分类" => {
-						sortid = FILTER_SORTID[index].to_string();
-					}
-					"文库地区" => {
-						rgroupid = FILTER_RGROUPID[index].to_string();
-					}
-					"是否动画" => {
-						anime = FILTER_ANIME[index].to_string();
-					}
-					"是否轻改" => {
-						quality = FILTER_QUALITY[index].to_string();
-					}
-					"连载状态" => {
-						isfull = FILTER_ISFULL[index].to_string();
-					}
-					"更新时间" => {
-						update = FILTER_UPDATE[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				order = FILTER_ORDER[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let tagid = filters.get("作品主题");
+	let sortid = filters.get("作品分类");
+	let rgroupid = filters.get("文库地区");
+	let order = filters.get("排序方式");
+	let anime = filters.get("是否动画");
+	let quality = filters.get("是否轻改");
+	let isfull = filters.get("连载状态");
+	let update = filters.get("更新时间");
 
 	let url = if query.is_empty() {
 		format!(
@@ -153,7 +61,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			page
 		)
 	};
-	let html = gen_request(url, HttpMethod::Get)
+	let html = PROFILE
+		.request(url, HttpMethod::Get)
 		.header("Referer", &format!("{}/search.html", BASE_URL))
 		.html()?;
 	let link = html.select("#pagelink");
@@ -167,14 +76,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let alternate_url = html.select("link[rel='alternate']").attr("href").read();
 
 	if alternate_url.contains("detail") {
-		let id = alternate_url
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = html_slug(alternate_url);
 		let cover = html.select(".book-cover").attr("src").read();
 		let title = html.select("h1.book-title").text().read();
 
@@ -190,16 +92,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.attr("href")
-				.read()
-				.split("/")
-				.map(|a| a.to_string())
-				.filter(|a| !a.is_empty())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap()
-				.replace(".html", "");
+			let id = html_slug(item.attr("href").read());
 			let cover = item.select(".book-cover>img").attr("data-src").read();
 			let title = item.select(".book-title").text().read();
 			mangas.push(Manga {
@@ -262,7 +155,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/top/{}/1.html", BASE_URL, name);
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let html = PROFILE.request(url, HttpMethod::Get).html()?;
 	let has_more = false;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -271,16 +164,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = html_slug(item.attr("href").read());
 		let cover = item.select(".book-cover>img").attr("data-src").read();
 		let title = item.select(".book-title").text().read();
 		mangas.push(Manga {
@@ -300,7 +184,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/detail/{}.html", BASE_URL, id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let html = PROFILE.request(url.clone(), HttpMethod::Get).html()?;
 	let cover = html.select(".book-cover").attr("src").read();
 	let title = html.select("h1.book-title").text().read();
 	let author = html
@@ -353,7 +237,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/read/{}/catalog", BASE_URL, id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let html = PROFILE.request(url.clone(), HttpMethod::Get).html()?;
 	let volumes = html.select(".catalog-volume").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
@@ -382,7 +266,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			if has_javascript_link {
 				let vol_href = volume.select(".volume-cover-img").attr("href").read();
 				let vol_url = format!("{}{}", BASE_URL, vol_href);
-				let vol_html = gen_request(vol_url, HttpMethod::Get).html()?;
+				let vol_html = PROFILE.request(vol_url, HttpMethod::Get).html()?;
 
 				for chapter_item in vol_html.select(".catalog-volume .chapter-li-a").array() {
 					let chapter_item = match chapter_item.as_node() {
@@ -390,16 +274,10 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 						Err(_) => continue,
 					};
 					let chapter_href = chapter_item.attr("href").read();
-					let chapter_id = chapter_href
-						.split("/")
-						.map(|a| a.to_string())
-						.filter(|a| !a.is_empty())
-						.collect::<Vec<String>>()
-						.pop()
-						.unwrap()
-						.replace(".html", "");
+					let chapter_id = html_slug(&chapter_href);
 					let title = chapter_item.select("span").text().read();
-					let chapter_num = extract_chapter_number(&title).unwrap_or(chapters.len() as f32 + 1.0);
+					let chapter_num =
+						extract_chapter_number(&title).unwrap_or(chapters.len() as f32 + 1.0);
 					let url = format!("{}{}", BASE_URL, chapter_href);
 					chapters.push(Chapter {
 						id: chapter_id,
@@ -418,16 +296,10 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 						Err(_) => continue,
 					};
 					let chapter_href = item.attr("href").read();
-					let chapter_id = chapter_href
-						.split("/")
-						.map(|a| a.to_string())
-						.filter(|a| !a.is_empty())
-						.collect::<Vec<String>>()
-						.pop()
-						.unwrap()
-						.replace(".html", "");
+					let chapter_id = html_slug(&chapter_href);
 					let title = item.select("span").text().read();
-					let chapter_num = extract_chapter_number(&title).unwrap_or(chapters.len() as f32 + 1.0);
+					let chapter_num =
+						extract_chapter_number(&title).unwrap_or(chapters.len() as f32 + 1.0);
 					let url = format!("{}{}", BASE_URL, chapter_href);
 					chapters.push(Chapter {
 						id: chapter_id,
@@ -454,7 +326,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let html = PROFILE.request(url.clone(), HttpMethod::Get).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select("#acontentz>img").array().enumerate() {
@@ -476,5 +348,27 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("User-Agent", UA).header("Referer", BASE_URL);
+	request
+		.header("User-Agent", MOBILE_UA)
+		.header("Referer", BASE_URL);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, slug] if kind == "detail" => (html_slug(slug), None),
+		[kind, id, catalog] if kind == "read" && catalog == "catalog" => (id.clone(), None),
+		// Later pages of a chapter are `<chapter>_<page>.html`.
+		[kind, id, slug] if kind == "read" => {
+			let chapter_id = html_slug(slug);
+			let chapter_id = match chapter_id.split_once('_') {
+				Some((chapter_id, _)) => chapter_id.to_string(),
+				None => chapter_id,
+			};
+			(id.clone(), Some(chapter_id))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
 }
@@ -0,0 +1,95 @@
+use aidoku::{
+	harness::{filter, Harness},
+	std::String,
+	Listing,
+};
+use aidoku_bilicomic::{
+	get_chapter_list, get_manga_details, get_manga_list, get_manga_listing, get_page_list,
+	handle_url,
+};
+
+fn harness() -> Harness {
+	Harness::new(env!("CARGO_MANIFEST_DIR")).fixtures("tests/fixtures")
+}
+
+#[test]
+fn manga_list() {
+	let harness = harness();
+	let filters = vec![
+		filter::select("作品主题", 1),
+		filter::select("连载状态", 2),
+		filter::sort("排序方式", 0, false),
+	];
+	let result = get_manga_list(filters, 1).unwrap();
+	harness.golden("manga_list", &result);
+
+	let request = &harness.requests()[0];
+	assert_eq!(request.header("Cookie"), Some("night=0"));
+	assert_eq!(
+		request.header("Referer"),
+		Some("https://www.bilimanga.net/search.html")
+	);
+}
+
+#[test]
+fn search_redirects_to_details() {
+	let harness = harness();
+	let result = get_manga_list(vec![filter::title("藥屋")], 1).unwrap();
+	harness.golden("search", &result);
+}
+
+#[test]
+fn rank_listing() {
+	let harness = harness();
+	let listing = Listing {
+		name: String::from("月点击榜"),
+	};
+	let result = get_manga_listing(listing, 1).unwrap();
+	harness.golden("rank_listing", &result);
+}
+
+#[test]
+fn manga_details() {
+	let harness = harness();
+	let manga = get_manga_details(String::from("3015")).unwrap();
+	harness.golden("manga_details", &manga);
+}
+
+#[test]
+fn chapter_list() {
+	let harness = harness();
+	let chapters = get_chapter_list(String::from("3015")).unwrap();
+	harness.golden("chapter_list", &chapters);
+}
+
+#[test]
+fn page_list() {
+	let harness = harness();
+	let pages = get_page_list(String::from("3015"), String::from("100101")).unwrap();
+	harness.golden("page_list", &pages);
+}
+
+#[test]
+fn deep_links() {
+	let _harness = harness();
+
+	let link = handle_url(String::from("https://www.bilimanga.net/detail/3015.html")).unwrap();
+	assert_eq!(link.manga.unwrap().id, "3015");
+	assert!(link.chapter.is_none());
+
+	let link = handle_url(String::from("https://www.bilimanga.net/read/3015/catalog")).unwrap();
+	assert_eq!(link.manga.unwrap().id, "3015");
+	assert!(link.chapter.is_none());
+
+	let link = handle_url(String::from(
+		"https://www.bilimanga.net/read/3015/100101_2.html",
+	))
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "3015");
+	assert_eq!(link.chapter.unwrap().id, "100101");
+
+	assert!(handle_url(String::from(
+		"https://www.bilimanga.net/top/monthvisit/1.html"
+	))
+	.is_err());
+}
@@ -7,22 +7,19 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		html::Node,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{html::Node, net::HttpMethod, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	id::html_slug,
+	link::{deep_link, unsupported},
+	request::gen_request,
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://www.cartoonmad.com";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
-
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
-}
 
 fn handle_img_url(url: String) -> String {
 	if url.starts_with("http") {
@@ -60,17 +57,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".a1")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = html_slug(item.select(".a1").attr("href").read());
 		let cover = format!("{}{}", WWW_URL, item.select("img").attr("src").read());
 		let title = item.select(".covertxt+a").attr("title").read();
 		mangas.push(Manga {
@@ -113,17 +100,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".a1")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = html_slug(item.select(".a1").attr("href").read());
 		let cover = format!("{}{}", WWW_URL, item.select("img").attr("src").read());
 		let title = item.select(".covertxt+a").attr("title").read();
 		mangas.push(Manga {
@@ -224,16 +201,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = html_slug(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
 		let chapter = (index + 1) as f32;
 		let url = format!("{}/m/comic/{}.html", WWW_URL, id.clone());
@@ -278,3 +246,25 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let slug = match path_segments(&url).as_slice() {
+		[m, comic, slug] if m == "m" && comic == "comic" => html_slug(slug),
+		[comic, slug] if comic == "comic" => html_slug(slug),
+		_ => return Err(unsupported()),
+	};
+
+	// Chapter pages are `<manga id><chapter:4><flag:1><pages:3><page:3>`, and
+	// the chapter list links to the first page of each chapter.
+	if slug.len() > 11 {
+		let (manga_id, chapter) = slug.split_at(slug.len() - 11);
+		let chapter_id = format!("{}{}001", manga_id, &chapter[..8]);
+		Ok(deep_link(
+			get_manga_details(manga_id.to_string())?,
+			Some(chapter_id),
+		))
+	} else {
+		Ok(deep_link(get_manga_details(slug)?, None))
+	}
+}
@@ -1,65 +1,241 @@
 use aidoku::{
-	helpers::uri::encode_uri,
+	helpers::uri::{encode_uri, QueryParameters},
 	prelude::*,
 	std::{
+		current_date,
+		defaults::{defaults_get, defaults_set},
 		html::Node,
+		json,
 		net::{HttpMethod, Request},
-		ObjectRef, String,
+		ObjectRef, String, StringRef, ValueRef, Vec,
 	},
 };
+use alloc::string::ToString;
+use base64::{engine::general_purpose, Engine};
 
 use crate::crypto;
+use zh_common::{
+	error::{check_response, Result, SourceError},
+	request::gen_request,
+};
+
+/// The mirror used when the `domain` setting is unset. Every mirror serves the
+/// site on `www.` and the API on `api.`.
+const DEFAULT_DOMAIN: &str = "mangacopy.com";
+
+/// Reads a setting, or `default` when it is unset or empty.
+fn setting(key: &str, default: &str) -> String {
+	defaults_get(key)
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.ok()
+		.filter(|value| !value.is_empty())
+		.unwrap_or_else(|| String::from(default))
+}
+
+fn domain() -> String {
+	setting("domain", DEFAULT_DOMAIN)
+}
+
+fn www_url() -> String {
+	format!("https://www.{}", domain())
+}
+
+fn api_url() -> String {
+	format!("https://api.{}/api/v3", domain())
+}
 
-const WWW_URL: &str = "https://www.mangacopy.com";
-const API_URL: &str = "https://api.mangacopy.com/api/v3";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
+/// Decrypts `text`, a 16 character IV followed by hex, and parses the JSON
+/// inside. Each key is tried in turn until one unpads to valid JSON.
+pub fn decrypt(text: String, keys: Vec<String>) -> Result<ValueRef> {
+	if text.len() <= 16 || !text.is_char_boundary(16) {
+		return Err(SourceError::Redesign.into());
+	}
+	let (iv, cipher) = text.split_at(16);
+	let cipher = hex::decode(cipher).map_err(|_| SourceError::Redesign)?;
 
-pub fn decrypt(text: String, key: String) -> String {
-	let text = text.as_bytes();
-	let key = key.as_bytes();
-	let key: &[u8; 16] = key.try_into().unwrap();
-	let iv = &text[..16];
-	let cipher = &text[16..];
-	let cipher = hex::decode(cipher).unwrap();
-	let pt = crypto::decrypt(&cipher, key, iv).unwrap();
-	String::from_utf8_lossy(&pt).replace("", "")
+	keys.iter()
+		.find_map(|key| {
+			let pt = crypto::decrypt(&cipher, key.as_bytes(), iv.as_bytes()).ok()?;
+			json::parse(String::from_utf8(pt).ok()?).ok()
+		})
+		.ok_or_else(|| SourceError::Redesign.into())
 }
 
-pub fn get_text(url: String) -> String {
-	Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.string()
-		.unwrap()
+/// Every 16 character string assigned in a page, such as `var ccx = '…'`, in
+/// the order they appear. The site renames these variables every few months,
+/// so the keys are found by their length instead.
+pub fn find_keys(text: &str) -> Vec<String> {
+	let mut keys = Vec::new();
+	for value in assigned_strings(text) {
+		if value.len() == 16
+			&& value.chars().all(|c| c.is_ascii_graphic())
+			&& !keys.contains(&value)
+		{
+			keys.push(value);
+		}
+	}
+	keys
 }
 
-pub fn get_html(url: String) -> Node {
-	Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.html()
-		.unwrap()
+/// The encrypted page list of a chapter page: the longest assigned string
+/// made of a 16 character IV and hex.
+pub fn find_payload(text: &str) -> Option<String> {
+	assigned_strings(text)
+		.filter(|value| {
+			value.len() > 16
+				&& value.get(16..).is_some_and(|hex| {
+					hex.len() % 2 == 0 && hex.chars().all(|c| c.is_ascii_hexdigit())
+				})
+		})
+		.max_by_key(|value| value.len())
 }
 
-pub fn get_json(url: String) -> ObjectRef {
-	let request = Request::new(url.clone(), HttpMethod::Get);
+/// The quoted values following an `=`, from script variables and HTML
+/// attributes alike.
+fn assigned_strings(text: &str) -> impl Iterator<Item = String> + '_ {
+	text.split('=').skip(1).filter_map(|rest| {
+		let rest = rest.trim_start();
+		let quote = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+		let value = &rest[1..];
+		let end = value.find(quote)?;
+		Some(value[..end].to_string())
+	})
+}
+
+/// Sends a request to the site, failing on a Cloudflare challenge or a
+/// server error instead of parsing the page that came back. The site reads
+/// the same token as the API from the `token` cookie, so chapters that need
+/// an account are only served with it.
+fn get_www(url: String) -> Result<Request> {
+	let request = match session() {
+		Some(token) => {
+			gen_request(url, HttpMethod::Get).header("Cookie", &format!("token={}", token))
+		}
+		None => gen_request(url, HttpMethod::Get),
+	};
+	request.send();
+	check_response(&request)?;
+	Ok(request)
+}
 
-	let request = if url.starts_with(WWW_URL) {
-		request.header("User-Agent", UA)
+pub fn get_text(url: String) -> Result<String> {
+	Ok(get_www(url)?.string()?)
+}
+
+pub fn get_html(url: String) -> Result<Node> {
+	Ok(get_www(url)?.html()?)
+}
+
+pub fn get_json(url: String) -> Result<ObjectRef> {
+	if url.starts_with(&www_url()) {
+		return Ok(get_www(url)?.json()?.as_object()?);
+	}
+
+	let request = gen_api_request(&url, HttpMethod::Get, session());
+
+	request.send();
+
+	// A token that expired or was revoked is answered with 401.
+	let request = if request.status_code() == 401 {
+		let request = gen_api_request(&url, HttpMethod::Get, Some(login()?));
+		request.send();
+		request
 	} else {
 		request
-			.header("User-Agent", "COPY/2.3.1")
-			.header("version", "2.3.1")
-			.header("platform", "3")
-			.header("region", "1")
-			.header("webp", "1")
 	};
+	check_response(&request)?;
+
+	Ok(request.json()?.as_object()?)
+}
+
+fn gen_api_request(url: &str, method: HttpMethod, token: Option<String>) -> Request {
+	let request = Request::new(url, method)
+		.header("User-Agent", "COPY/2.3.1")
+		.header("version", "2.3.1")
+		.header("platform", "3")
+		.header("region", "1")
+		.header(
+			"webp",
+			if setting("format", "webp") == "webp" {
+				"1"
+			} else {
+				"0"
+			},
+		);
+
+	match token {
+		Some(token) => request.header("Authorization", &format!("Token {}", token)),
+		None => request,
+	}
+}
 
-	request.json().unwrap().as_object().unwrap()
+/// The token of the last login. Browsing works without one, so requests only
+/// log in once the API answers 401.
+pub fn session() -> Option<String> {
+	Some(setting("session", "")).filter(|token| !token.is_empty())
+}
+
+/// Logs in with the username and password from the settings and keeps the
+/// token as `session`, which is not a setting the user can edit.
+pub fn login() -> Result<String> {
+	let username = setting("username", "");
+	let password = setting("password", "");
+
+	if username.is_empty() || password.is_empty() {
+		return Err(SourceError::Auth.into());
+	}
+
+	// The app sends the password as base64 of `{password}-{salt}` next to
+	// the salt itself.
+	let salt = (current_date() as i64 % 10000).to_string();
+	let mut body = QueryParameters::new();
+	body.push("username", Some(&username));
+	body.push(
+		"password",
+		Some(&general_purpose::STANDARD.encode(format!("{}-{}", password, salt))),
+	);
+	body.push("salt", Some(&salt));
+	body.push("source", Some("freeSite"));
+	body.push("version", Some("2.3.1"));
+	body.push("platform", Some("3"));
+	let body = body.to_string();
+
+	let request = gen_api_request(&gen_login_url(), HttpMethod::Post, None)
+		.header(
+			"Content-Type",
+			"application/x-www-form-urlencoded;charset=utf-8",
+		)
+		.body(body.as_bytes());
+
+	request.send();
+	check_response(&request)?;
+
+	// A wrong username or password is answered with 200 and a code of 210.
+	let json = request.json()?.as_object()?;
+	if json.get("code").as_int().unwrap_or_default() != 200 {
+		return Err(SourceError::Auth.into());
+	}
+	let token = json
+		.get("results")
+		.as_object()
+		.and_then(|results| results.get("token").as_string())
+		.map_err(|_| SourceError::Redesign)?
+		.read();
+
+	defaults_set("session", StringRef::from(token.clone()).0);
+
+	Ok(token)
+}
+
+pub fn gen_login_url() -> String {
+	format!("{}/login", api_url())
 }
 
 pub fn gen_explore_url(theme: String, top: String, ordering: String, page: i32) -> String {
 	format!(
 		"{}/comics?theme={}&top={}&ordering={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		theme,
 		top,
 		ordering,
@@ -71,7 +247,7 @@ pub fn gen_explore_url(theme: String, top: String, ordering: String, page: i32)
 pub fn gen_search_url(query: String, page: i32) -> String {
 	format!(
 		"{}/search/comic?q={}&q_type={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		encode_uri(query),
 		"",
 		20,
@@ -82,7 +258,7 @@ pub fn gen_search_url(query: String, page: i32) -> String {
 pub fn gen_rank_url(date_type: String, page: i32) -> String {
 	format!(
 		"{}/ranks?date_type={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		date_type,
 		30,
 		(page - 1) * 30,
@@ -92,7 +268,7 @@ pub fn gen_rank_url(date_type: String, page: i32) -> String {
 pub fn gen_recs_url(page: i32) -> String {
 	format!(
 		"{}/recs?pos={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		"3200102",
 		30,
 		(page - 1) * 30,
@@ -102,28 +278,68 @@ pub fn gen_recs_url(page: i32) -> String {
 pub fn gen_newest_url(page: i32) -> String {
 	format!(
 		"{}/update/newest?limit={}&offset={}",
-		API_URL,
+		api_url(),
+		30,
+		(page - 1) * 30,
+	)
+}
+
+pub fn gen_collect_url(page: i32) -> String {
+	format!(
+		"{}/member/collect/comics?free_type={}&ordering={}&limit={}&offset={}",
+		api_url(),
+		1,
+		"-datetime_modifier",
 		30,
 		(page - 1) * 30,
 	)
 }
 
+/// Rewrites the size and format suffix of a page image, as in
+/// `001.jpg.c800x.webp`, to the ones picked in the settings. URLs without
+/// that suffix are returned as is.
+pub fn gen_image_url(url: String) -> String {
+	let Some(start) = url.rfind(".c") else {
+		return url;
+	};
+	let suffix = &url[start + 2..];
+	let Some((width, _)) = suffix.split_once("x.") else {
+		return url;
+	};
+	if width.is_empty() || !width.chars().all(|c| c.is_ascii_digit()) {
+		return url;
+	}
+
+	// The CDN only has c800x and c1500x, so a value saved by an older
+	// version, such as 1200, falls back to 800.
+	let resolution = match setting("resolution", "800").as_str() {
+		"1500" => "1500",
+		_ => "800",
+	};
+	format!(
+		"{}.c{}x.{}",
+		&url[..start],
+		resolution,
+		setting("format", "webp")
+	)
+}
+
 pub fn gen_manga_url(id: String) -> String {
-	format!("{}/comic/{}", WWW_URL, id)
+	format!("{}/comic/{}", www_url(), id)
 }
 
-// pub fn gen_manga_details_url(id: String) -> String {
-// 	format!("{}/comic2/{}", API_URL, id)
-// }
+pub fn gen_manga_details_url(id: String) -> String {
+	format!("{}/comic2/{}", api_url(), id)
+}
 
 pub fn gen_chapter_list_url(id: String) -> String {
-	format!("{}/comicdetail/{}/chapters", WWW_URL, id)
+	format!("{}/comicdetail/{}/chapters", www_url(), id)
 }
 
 pub fn gen_chapter_url(manga_id: String, chapter_id: String) -> String {
-	format!("{}/comic/{}/chapter/{}", WWW_URL, manga_id, chapter_id)
+	format!("{}/comic/{}/chapter/{}", www_url(), manga_id, chapter_id)
 }
 
 pub fn gen_page_list_url(manga_id: String, chapter_id: String) -> String {
-	format!("{}/comic/{}/chapter/{}", WWW_URL, manga_id, chapter_id)
+	format!("{}/comic/{}/chapter/{}", www_url(), manga_id, chapter_id)
 }
@@ -2,122 +2,37 @@
 extern crate alloc;
 
 use aidoku::{
-	error::Result,
-	helpers::substring::Substring,
+	error::{AidokuError, Result},
 	prelude::*,
-	std::{json, String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaViewer,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaViewer,
 	Page,
 };
-use alloc::string::ToString;
+use zh_common::{
+	error::{self, page_list, SourceError},
+	filter::FilterValues,
+	json::parse_list,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 mod crypto;
 mod helper;
 mod parser;
 
-const FILTER_THEME: [&str; 61] = [
-	"",
-	"aiqing",
-	"huanlexiang",
-	"maoxian",
-	"qihuan",
-	"baihe",
-	"xiaoyuan",
-	"kehuan",
-	"dongfang",
-	"danmei",
-	"shenghuo",
-	"gedou",
-	"qingxiaoshuo",
-	"xuanyi",
-	"qita",
-	"shengui",
-	"zhichang",
-	"mengxi",
-	"teenslove",
-	"zhiyu",
-	"changtiao",
-	"sige",
-	"jiecao",
-	"jianniang",
-	"jingji",
-	"gaoxiao",
-	"weiniang",
-	"rexue",
-	"lizhi",
-	"hougong",
-	"meishi",
-	"xingzhuanhuan",
-	"zhentan",
-	"COLOR",
-	"aa",
-	"yinyuewudao",
-	"yishijie",
-	"zhanzheng",
-	"lishi",
-	"jingsong",
-	"jizhan",
-	"mohuan",
-	"dushi",
-	"chuanyue",
-	"kongbu",
-	"comiket100",
-	"chongsheng",
-	"comiket99",
-	"comiket97",
-	"comiket101",
-	"comiket96",
-	"zhaixi",
-	"wuxia",
-	"shengcun",
-	"C98",
-	"comiket95",
-	"fate",
-	"zhuansheng",
-	"Uncensored",
-	"xianxia",
-	"loveLive",
-];
-const FILTER_TOP: [&str; 4] = ["", "japan", "korea", "west"];
-const FILTER_ORDERING: [&str; 2] = ["popular", "datetime_updated"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut theme = String::new();
-	let mut top = String::new();
-	let mut ordering = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"题材" => {
-						theme = FILTER_THEME[index].to_string();
-					}
-					"地区" => {
-						top = FILTER_TOP[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				let ascending = value.get("ascending").as_bool().unwrap_or(false);
-				ordering.push_str(if ascending { "" } else { "-" });
-				ordering.push_str(FILTER_ORDERING[index]);
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let theme = filters.get("题材");
+	let top = filters.get("地区");
+	let ordering = format!(
+		"{}{}",
+		if filters.ascending() { "" } else { "-" },
+		filters.get("排序")
+	);
 
 	let url = if query.is_empty() {
 		helper::gen_explore_url(theme, top, ordering, page)
@@ -125,13 +40,13 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		helper::gen_search_url(query, page)
 	};
 
-	let json = helper::get_json(url);
+	let json = helper::get_json(url)?;
 	let data = json.get("results").as_object()?;
 	let list = data.get("list").as_array()?;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
-		has_more: parser::has_more(data),
+		manga: parse_list(list, parser::parse_manga),
+		has_more: parser::has_more(data)?,
 	})
 }
 
@@ -140,6 +55,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let mut date_type = String::new();
 	let mut is_recs = false;
 	let mut is_newest = false;
+	let mut is_collect = false;
 
 	match listing.name.as_str() {
 		"日榜" => {
@@ -160,6 +76,14 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"全新上架" => {
 			is_newest = true;
 		}
+		"我的书架" => {
+			// The bookshelf always needs an account, so this logs in up front
+			// instead of waiting for the 401.
+			if helper::session().is_none() {
+				helper::login()?;
+			}
+			is_collect = true;
+		}
 		_ => return get_manga_list(Vec::new(), page),
 	}
 
@@ -169,24 +93,38 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		helper::gen_recs_url(page)
 	} else if is_newest {
 		helper::gen_newest_url(page)
+	} else if is_collect {
+		helper::gen_collect_url(page)
 	} else {
 		String::new()
 	};
 
-	let json = helper::get_json(url);
+	let json = helper::get_json(url)?;
 	let data = json.get("results").as_object()?;
 	let list = data.get("list").as_array()?;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
-		has_more: parser::has_more(data),
+		manga: parse_list(list, parser::parse_manga),
+		has_more: parser::has_more(data)?,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
+	let url = helper::gen_manga_details_url(id.clone());
+
+	// The web page has no content rating, so it is only a fallback for when the
+	// API fails.
+	helper::get_json(url)
+		.map_err(AidokuError::from)
+		.and_then(|json| json.get("results").as_object())
+		.and_then(parser::parse_manga)
+		.or_else(|_| get_manga_details_from_html(id))
+}
+
+fn get_manga_details_from_html(id: String) -> Result<Manga> {
 	let url = helper::gen_manga_url(id.clone());
-	let html = helper::get_html(url.clone());
+	let html = helper::get_html(url.clone())?;
 	let cover = html
 		.select(".comicParticulars-left-img>img")
 		.attr("data-src")
@@ -195,7 +133,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let author = html
 		.select(".comicParticulars-right-txt>a")
 		.array()
-		.map(|a| a.as_node().unwrap().text().read())
+		.filter_map(|a| Some(a.as_node().ok()?.text().read()))
 		.collect::<Vec<String>>()
 		.join(", ");
 	let artist = String::new();
@@ -203,7 +141,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let categories = html
 		.select(".comicParticulars-tag>a")
 		.array()
-		.map(|a| a.as_node().unwrap().text().read().replace("#", ""))
+		.filter_map(|a| Some(a.as_node().ok()?.text().read().replace("#", "")))
 		.collect::<Vec<String>>();
 	let full_title = html.select("title").text().read();
 	let status = if full_title.contains("連載中") {
@@ -234,40 +172,39 @@ fn get_manga_details(id: String) -> Result<Manga> {
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let manga_url = helper::gen_manga_url(id.clone());
-	let text = helper::get_text(manga_url);
-	let key = text
-		.substring_after("var ccx = '")
-		.unwrap()
-		.substring_before("'")
-		.unwrap()
-		.to_string();
+	let keys = helper::find_keys(&helper::get_text(manga_url)?);
 	let url = helper::gen_chapter_list_url(id);
-	let json = helper::get_json(url);
+	let json = helper::get_json(url)?;
 	let data = json.get("results").as_string()?.read();
-	let data = helper::decrypt(data, key);
-	let data = json::parse(data)?.as_object()?;
+	let data = helper::decrypt(data, keys)?.as_object()?;
 
-	Ok(parser::parse_chapter_list(data))
+	parser::parse_chapter_list(data)
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
+	page_list(fetch_page_list(manga_id, chapter_id))
+}
+
+fn fetch_page_list(manga_id: String, chapter_id: String) -> error::Result<Vec<Page>> {
 	let url = helper::gen_page_list_url(manga_id, chapter_id);
-	let text = helper::get_text(url);
-	let key = text
-		.substring_after("var ccy = '")
-		.unwrap()
-		.substring_before("'")
-		.unwrap()
-		.to_string();
-	let data = text
-		.substring_after("contentKey=\"")
-		.unwrap()
-		.substring_before("\"")
-		.unwrap()
-		.to_string();
-	let data = helper::decrypt(data, key);
-	let data = json::parse(data)?.as_array()?;
+	let text = helper::get_text(url)?;
+	let keys = helper::find_keys(&text);
+	let data = helper::find_payload(&text).ok_or(SourceError::Redesign)?;
+	let data = helper::decrypt(data, keys)?.as_array()?;
 
 	Ok(parser::parse_page_list(data))
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		[kind, id, chapter, chapter_id] if kind == "comic" && chapter == "chapter" => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
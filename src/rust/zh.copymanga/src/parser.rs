@@ -1,7 +1,7 @@
 use core::str::FromStr;
 
 use aidoku::{
-	prelude::*,
+	error::Result,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
@@ -10,39 +10,35 @@ use alloc::string::ToString;
 use crate::helper;
 use uuid::Uuid;
 
-pub fn has_more(data: ObjectRef) -> bool {
-	let total = data.get("total").as_int().unwrap();
-	let limit = data.get("limit").as_int().unwrap();
-	let offset = data.get("offset").as_int().unwrap();
-	total > limit + offset
+pub fn has_more(data: ObjectRef) -> Result<bool> {
+	let total = data.get("total").as_int()?;
+	let limit = data.get("limit").as_int()?;
+	let offset = data.get("offset").as_int()?;
+	Ok(total > limit + offset)
 }
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
-	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
-}
-
-pub fn parse_manga(manga: ObjectRef) -> Manga {
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
 	let manga = match manga.get("comic").as_object() {
 		Ok(value) => value,
 		Err(_) => manga,
 	};
-	let id = manga.get("path_word").as_string().unwrap().read();
-	let cover = manga.get("cover").as_string().unwrap().read();
-	let title = manga.get("name").as_string().unwrap().read();
+	let id = manga.get("path_word").as_string()?.read();
+	let cover = manga.get("cover").as_string().unwrap_or_default().read();
+	let title = manga.get("name").as_string()?.read();
 	let author = manga
 		.get("author")
 		.as_array()
-		.unwrap()
-		.map(|author| {
-			author
-				.as_object()
-				.unwrap()
-				.get("name")
-				.as_string()
-				.unwrap()
-				.read()
+		.unwrap_or_default()
+		.filter_map(|author| {
+			Some(
+				author
+					.as_object()
+					.ok()?
+					.get("name")
+					.as_string()
+					.ok()?
+					.read(),
+			)
 		})
 		.collect::<Vec<String>>()
 		.join(", ");
@@ -59,15 +55,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		.get("theme")
 		.as_array()
 		.unwrap_or_default()
-		.map(|theme| {
-			theme
-				.as_object()
-				.unwrap_or_default()
-				.get("name")
-				.as_string()
-				.unwrap_or_default()
-				.read()
-		})
+		.filter_map(|theme| Some(theme.as_object().ok()?.get("name").as_string().ok()?.read()))
 		.collect::<Vec<String>>();
 	let status = match manga
 		.get("status")
@@ -98,7 +86,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		_ => MangaContentRating::Safe,
 	};
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -110,94 +98,102 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
-pub fn parse_chapter_group(
-	manga_id: String,
-	group: ObjectRef,
-	name: String,
-	start: usize,
-) -> Vec<Chapter> {
-	let list = group.get("chapters").as_array();
+/// Parses one group of the chapter payload, numbering its chapters from 1 and
+/// newest first.
+pub fn parse_chapter_group(manga_id: &str, group: ObjectRef, name: &str) -> Vec<Chapter> {
+	let list = group.get("chapters").as_array().unwrap_or_default();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	if list.is_ok() {
-		for (index, item) in list.unwrap().enumerate() {
-			let chapter = item.as_object().unwrap();
-			let id = chapter.get("id").as_string().unwrap().read();
-			let title = format!(
-				"{} - {}",
-				name,
-				chapter.get("name").as_string().unwrap().read()
-			);
-			let chapter = (index + start + 1) as f32;
-			let (p1, p2) = Uuid::from_str(&id.clone())
-				.unwrap()
-				.get_timestamp()
-				.unwrap()
-				.to_unix();
-			let date_updated = (p1 as f64) + (p2 as f64 * 10e-10);
-			let url = helper::gen_chapter_url(manga_id.clone(), id.clone());
-			chapters.push(Chapter {
-				id,
-				title,
-				chapter,
-				date_updated,
-				url,
-				..Default::default()
-			})
+	for (index, item) in list.enumerate() {
+		let chapter = (index + 1) as f32;
+		if let Ok(item) = parse_chapter(manga_id, name, chapter, item.as_object()) {
+			chapters.push(item);
 		}
 	}
 
+	chapters.reverse();
 	chapters
 }
 
-pub fn parse_chapter_list(manga: ObjectRef) -> Vec<Chapter> {
-	let build = manga.get("build").as_object().unwrap();
-	let manga_id = build.get("path_word").as_string().unwrap().read();
-	let groups = manga.get("groups").as_object().unwrap();
-	let default_group = groups.get("default").as_object().unwrap_or_default();
-	let tankobon_group = groups.get("tankobon").as_object().unwrap_or_default();
-	let other_honyakuchimu_group = groups
-		.get("other_honyakuchimu")
-		.as_object()
-		.unwrap_or_default();
-	let karapeji_group = groups.get("karapeji").as_object().unwrap_or_default();
-	let default = parse_chapter_group(manga_id.clone(), default_group, String::from("默认"), 0);
-	let tankobon = parse_chapter_group(
-		manga_id.clone(),
-		tankobon_group,
-		String::from("单行本"),
-		default.len(),
-	);
-	let other_honyakuchimu = parse_chapter_group(
-		manga_id.clone(),
-		other_honyakuchimu_group,
-		String::from("其它汉化版"),
-		default.len() + tankobon.len(),
-	);
-	let karapeji = parse_chapter_group(
-		manga_id.clone(),
-		karapeji_group,
-		String::from("全彩版"),
-		default.len() + tankobon.len() + other_honyakuchimu.len(),
-	);
-	let mut chapters = [default, tankobon, other_honyakuchimu, karapeji].concat();
+fn parse_chapter(
+	manga_id: &str,
+	group: &str,
+	number: f32,
+	chapter: Result<ObjectRef>,
+) -> Result<Chapter> {
+	let chapter = chapter?;
+	let id = chapter.get("id").as_string()?.read();
+	let title = chapter.get("name").as_string()?.read();
+	// The ids are v1 UUIDs, which carry the upload time.
+	let date_updated = Uuid::from_str(&id)
+		.ok()
+		.and_then(|uuid| uuid.get_timestamp())
+		.map(|timestamp| {
+			let (p1, p2) = timestamp.to_unix();
+			(p1 as f64) + (p2 as f64 * 10e-10)
+		})
+		.unwrap_or(-1.0);
+	let url = helper::gen_chapter_url(manga_id.to_string(), id.clone());
 
-	chapters.reverse();
-	chapters
+	Ok(Chapter {
+		id,
+		title,
+		chapter: number,
+		date_updated,
+		scanlator: group.to_string(),
+		url,
+		..Default::default()
+	})
+}
+
+/// Parses every group of the chapter payload, `default` first and the others
+/// by key. Each group keeps its own numbering and shows its name as the
+/// scanlator.
+pub fn parse_chapter_list(manga: ObjectRef) -> Result<Vec<Chapter>> {
+	let build = manga.get("build").as_object()?;
+	let manga_id = build.get("path_word").as_string()?.read();
+	let groups = manga.get("groups").as_object()?;
+
+	let mut keys = groups
+		.keys()
+		.filter_map(|key| Some(key.as_string().ok()?.read()))
+		.collect::<Vec<String>>();
+	keys.sort_by_key(|key| (key != "default", key.clone()));
+
+	let mut chapters: Vec<Chapter> = Vec::new();
+	for key in keys {
+		let Ok(group) = groups.get(&key).as_object() else {
+			continue;
+		};
+		let name = group
+			.get("name")
+			.as_string()
+			.map(|name| name.read())
+			.ok()
+			.filter(|name| !name.is_empty())
+			.unwrap_or(key);
+		chapters.append(&mut parse_chapter_group(&manga_id, group, &name));
+	}
+
+	Ok(chapters)
 }
 
 pub fn parse_page_list(chapters: ArrayRef) -> Vec<Page> {
 	let mut pages: Vec<Page> = Vec::new();
 
-	for (index, item) in chapters.enumerate() {
-		let page = item.as_object().unwrap();
-		let index = index as i32;
-		let url = page.get("url").as_string().unwrap().read();
+	for item in chapters {
+		let Ok(url) = item
+			.as_object()
+			.and_then(|page| page.get("url").as_string())
+		else {
+			continue;
+		};
+		let url = helper::gen_image_url(url.read());
 		pages.push(Page {
-			index,
+			index: pages.len() as i32,
 			url,
 			..Default::default()
 		})
@@ -0,0 +1,267 @@
+use aidoku::{
+	harness::{filter, Harness},
+	std::String,
+	Listing, MangaContentRating,
+};
+use aidoku_copymanga::{
+	get_chapter_list, get_manga_details, get_manga_list, get_manga_listing, get_page_list,
+	handle_url,
+};
+use zh_common::error::SourceError;
+
+fn harness() -> Harness {
+	Harness::new(env!("CARGO_MANIFEST_DIR")).fixtures("tests/fixtures")
+}
+
+#[test]
+fn manga_list() {
+	let harness = harness();
+	let filters = vec![
+		filter::select("题材", 4),
+		filter::select("地区", 1),
+		filter::sort("排序", 1, false),
+	];
+	let result = get_manga_list(filters, 1).unwrap();
+	harness.golden("manga_list", &result);
+}
+
+#[test]
+fn search() {
+	let harness = harness();
+	let result = get_manga_list(vec![filter::title("妖神记")], 1).unwrap();
+	harness.golden("search", &result);
+}
+
+#[test]
+fn rank_listing() {
+	let harness = harness();
+	let listing = Listing {
+		name: String::from("日榜"),
+	};
+	let result = get_manga_listing(listing, 2).unwrap();
+	harness.golden("rank_listing", &result);
+}
+
+#[test]
+fn malformed_entries_are_skipped() {
+	let _harness = harness();
+	let listing = Listing {
+		name: String::from("周榜"),
+	};
+	let result = get_manga_listing(listing, 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|manga| manga.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["yaoshenji", "xinghe"]);
+	assert_eq!(result.manga[1].author, "");
+	assert!(!result.has_more);
+}
+
+#[test]
+fn bookshelf_logs_in_and_sends_the_token() {
+	let harness = harness()
+		.date(1700001234.0)
+		.default("username", "reader")
+		.default("password", "secret");
+	let listing = Listing {
+		name: String::from("我的书架"),
+	};
+	let result = get_manga_listing(listing, 1).unwrap();
+	assert_eq!(result.manga[0].id, "yaoshenji");
+	assert!(result.has_more);
+
+	let token = "3f1c2a9e8b7d6c5f4e3a2b1c0d9e8f7a6b5c4d3e";
+	let requests = harness.requests();
+	assert_eq!(requests.len(), 2);
+	let body = requests[0].body_string();
+	assert!(body.contains("password=c2VjcmV0LTEyMzQ%3D"));
+	assert!(body.contains("salt=1234"));
+	assert_eq!(
+		requests[1].header("Authorization"),
+		Some(format!("Token {}", token).as_str())
+	);
+	assert_eq!(harness.get_default("session").as_deref(), Some(token));
+}
+
+#[test]
+fn bookshelf_reuses_the_session() {
+	let harness = harness()
+		.default("username", "reader")
+		.default("password", "secret")
+		.default("session", "saved");
+	let listing = Listing {
+		name: String::from("我的书架"),
+	};
+	get_manga_listing(listing, 1).unwrap();
+
+	let requests = harness.requests();
+	assert_eq!(requests.len(), 1);
+	assert_eq!(requests[0].header("Authorization"), Some("Token saved"));
+}
+
+#[test]
+fn chapters_are_read_with_the_session() {
+	let harness = harness().default("session", "saved");
+	get_chapter_list(String::from("yaoshenji")).unwrap();
+	get_page_list(
+		String::from("yaoshenji"),
+		String::from("5fe94000-f5bc-11ea-8001-0242ac110002"),
+	)
+	.unwrap();
+
+	let requests = harness.requests();
+	assert_eq!(requests.len(), 3);
+	for request in requests {
+		assert_eq!(request.header("Cookie"), Some("token=saved"));
+	}
+}
+
+#[test]
+fn browsing_does_not_log_in() {
+	let harness = harness()
+		.default("username", "reader")
+		.default("password", "wrong");
+	get_manga_list(vec![filter::title("妖神记")], 1).unwrap();
+
+	let requests = harness.requests();
+	assert_eq!(requests.len(), 1);
+	assert!(!requests[0].url.ends_with("/login"));
+	assert_eq!(requests[0].header("Authorization"), None);
+}
+
+#[test]
+fn bookshelf_needs_a_valid_account() {
+	let harness = harness()
+		.default("domain", "copymanga.tv")
+		.default("username", "reader")
+		.default("password", "wrong");
+	let listing = Listing {
+		name: String::from("我的书架"),
+	};
+	assert!(get_manga_listing(listing, 1).is_err());
+	assert_eq!(harness.requests().len(), 1);
+	assert_eq!(harness.get_default("session"), None);
+}
+
+#[test]
+fn manga_details() {
+	let harness = harness();
+	let manga = get_manga_details(String::from("yaoshenji")).unwrap();
+	harness.golden("manga_details", &manga);
+}
+
+#[test]
+fn manga_details_fall_back_to_html() {
+	let harness = harness();
+	let manga = get_manga_details(String::from("xinghe")).unwrap();
+	assert_eq!(manga.title, "妖神記");
+	assert_eq!(manga.nsfw, MangaContentRating::Safe);
+	assert_eq!(
+		harness.requests()[0].url,
+		"https://api.mangacopy.com/api/v3/comic2/xinghe"
+	);
+}
+
+#[test]
+fn domain_setting_picks_the_mirror() {
+	let harness = harness().default("domain", "copymanga.tv");
+	let manga = get_manga_details(String::from("yaoshenji")).unwrap();
+	assert_eq!(manga.url, "https://www.copymanga.tv/comic/yaoshenji");
+	assert_eq!(manga.nsfw, MangaContentRating::Suggestive);
+	assert_eq!(harness.requests().len(), 1);
+}
+
+#[test]
+fn chapter_list() {
+	let harness = harness();
+	let chapters = get_chapter_list(String::from("yaoshenji")).unwrap();
+	harness.golden("chapter_list", &chapters);
+}
+
+#[test]
+fn page_list() {
+	let harness = harness();
+	let pages = get_page_list(
+		String::from("yaoshenji"),
+		String::from("5fe94000-f5bc-11ea-8001-0242ac110002"),
+	)
+	.unwrap();
+	harness.golden("page_list", &pages);
+}
+
+#[test]
+fn renamed_key_variables_are_found() {
+	let _harness = harness();
+	let chapters = get_chapter_list(String::from("renamed")).unwrap();
+	let expected = get_chapter_list(String::from("yaoshenji")).unwrap();
+	assert_eq!(
+		chapters.iter().map(|c| &c.title).collect::<Vec<_>>(),
+		expected.iter().map(|c| &c.title).collect::<Vec<_>>()
+	);
+
+	let pages = get_page_list(String::from("yaoshenji"), String::from("renamed")).unwrap();
+	assert_eq!(
+		pages[0].url,
+		"https://hi77-overseas.mangafuna.xyz/yaoshenji/1/001.jpg.c800x.webp"
+	);
+}
+
+#[test]
+fn page_list_without_a_working_key_explains_why() {
+	let _harness = harness();
+	let pages = get_page_list(String::from("yaoshenji"), String::from("nokey")).unwrap();
+	assert_eq!(pages, [SourceError::Redesign.page(0)]);
+}
+
+#[test]
+fn image_settings_rewrite_page_urls() {
+	let _harness = harness()
+		.default("resolution", "1500")
+		.default("format", "jpg");
+	let pages = get_page_list(
+		String::from("yaoshenji"),
+		String::from("5fe94000-f5bc-11ea-8001-0242ac110002"),
+	)
+	.unwrap();
+	assert_eq!(
+		pages[0].url,
+		"https://hi77-overseas.mangafuna.xyz/yaoshenji/1/001.jpg.c1500x.jpg"
+	);
+}
+
+#[test]
+fn unknown_resolutions_fall_back_to_800() {
+	let _harness = harness().default("resolution", "1200");
+	let pages = get_page_list(
+		String::from("yaoshenji"),
+		String::from("5fe94000-f5bc-11ea-8001-0242ac110002"),
+	)
+	.unwrap();
+	assert_eq!(
+		pages[0].url,
+		"https://hi77-overseas.mangafuna.xyz/yaoshenji/1/001.jpg.c800x.webp"
+	);
+}
+
+#[test]
+fn deep_links() {
+	let _harness = harness();
+
+	let link = handle_url(String::from("https://www.mangacopy.com/comic/yaoshenji")).unwrap();
+	assert_eq!(link.manga.unwrap().id, "yaoshenji");
+	assert!(link.chapter.is_none());
+
+	let link = handle_url(String::from(
+		"https://www.mangacopy.com/comic/yaoshenji/chapter/5fe94000-f5bc-11ea-8001-0242ac110002",
+	))
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "yaoshenji");
+	assert_eq!(
+		link.chapter.unwrap().id,
+		"5fe94000-f5bc-11ea-8001-0242ac110002"
+	);
+
+	assert!(handle_url(String::from("https://www.mangacopy.com/rank")).is_err());
+}
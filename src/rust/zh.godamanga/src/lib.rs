@@ -9,90 +9,29 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter::find_chapter,
+	filter::FilterValues,
+	id::{join_pair, last_segment, split_pair},
+	link::unsupported,
+	url::{path_segments, unproxy},
+};
 
 const WWW_URL: &str = "https://godamh.com";
 const API_URL: &str = "https://api-get-v2.mgsearcher.com";
 const IMG_URL: &str = "https://f40-1-4.g-mh.online";
 
-const FILTER_CATEGORY: [&str; 35] = [
-	"",
-	"cn",
-	"kr",
-	"jp",
-	"fuchou",
-	"gufeng",
-	"qihuan",
-	"nixi",
-	"lianai",
-	"yineng",
-	"zhaixiang",
-	"chuanyue",
-	"rexue",
-	"chunai",
-	"xitong",
-	"chongsheng",
-	"maoxian",
-	"lingyi",
-	"danvzhu",
-	"juqing",
-	"lianai",
-	"xuanhuan",
-	"nvshen",
-	"kehuan",
-	"mohuan",
-	"tuili",
-	"lieqi",
-	"zhiyu",
-	"dushi",
-	"yixing",
-	"qingchun",
-	"mori",
-	"xuanyi",
-	"xiuxian",
-	"zhandou",
-];
-
-fn handle_cover_url(url: String) -> String {
-	if url.contains("url=") {
-		url.split("url=")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace("%3A", ":")
-			.replace("%2F", "/")
-			.replace("&w=250&q=60", "")
-	} else {
-		url
-	}
-}
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let category = filters.get("类型");
 
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
@@ -117,15 +56,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
-		let cover = handle_cover_url(item.select("div>img").attr("src").read());
+		let id = last_segment(item.attr("href").read());
+		let cover = unproxy(item.select("div>img").attr("src").read());
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
 			id,
@@ -168,15 +100,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
-		let cover = handle_cover_url(item.select("div>img").attr("src").read());
+		let id = last_segment(item.attr("href").read());
+		let cover = unproxy(item.select("div>img").attr("src").read());
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
 			id,
@@ -194,11 +119,11 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/manga/{}", WWW_URL, ids[0]);
+	let (slug, _) = split_pair(&id);
+	let url = format!("{}/manga/{}", WWW_URL, slug);
 	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
 	let mid = html.select("#mangachapters").attr("data-mid").read();
-	let cover = handle_cover_url(
+	let cover = unproxy(
 		html.select("meta[property='og:image']")
 			.attr("content")
 			.read(),
@@ -235,7 +160,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let viewer = MangaViewer::Scroll;
 
 	Ok(Manga {
-		id: format!("{}/{}", ids[0], mid),
+		id: join_pair(&slug, &mid),
 		cover,
 		title,
 		author,
@@ -251,8 +176,8 @@ fn get_manga_details(id: String) -> Result<Manga> {
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/api/manga/get?mid={}&mode=all", API_URL, ids[1]);
+	let (slug, mid) = split_pair(&id);
+	let url = format!("{}/api/manga/get?mid={}&mode=all", API_URL, mid);
 	let json = Request::new(url.clone(), HttpMethod::Get)
 		.header("Origin", &WWW_URL)
 		.header("Referer", &WWW_URL)
@@ -270,8 +195,8 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		let attributes = item.get("attributes").as_object()?;
 		let id = item.get("id").as_int()?.to_string();
 		let title = attributes.get("title").as_string()?.read();
-		let slug = attributes.get("slug").as_string()?.read();
-		let url = format!("{}/manga/{}/{}", WWW_URL, ids[0], slug);
+		let chapter_slug = attributes.get("slug").as_string()?.read();
+		let url = format!("{}/manga/{}/{}", WWW_URL, slug, chapter_slug);
 		let chapter = (index + 1) as f32;
 		chapters.push(Chapter {
 			id,
@@ -288,11 +213,11 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
-	let ids = manga_id.split("/").collect::<Vec<&str>>();
+	let (_, mid) = split_pair(&manga_id);
 	let url = format!(
 		"{}/api/chapter/getinfo?m={}&c={}",
 		API_URL,
-		ids[1],
+		mid,
 		chapter_id.clone()
 	);
 	let json = Request::new(url.clone(), HttpMethod::Get)
@@ -330,3 +255,24 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", &WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = path_segments(&url);
+	let slug = match segments.as_slice() {
+		[kind, slug, ..] if kind == "manga" => slug.clone(),
+		_ => return Err(unsupported()),
+	};
+	let manga = get_manga_details(slug)?;
+	// Chapter IDs are not part of the chapter URL.
+	let chapter = if segments.len() > 2 {
+		find_chapter(get_chapter_list(manga.id.clone())?, &url)
+	} else {
+		None
+	};
+
+	Ok(DeepLink {
+		manga: Some(manga),
+		chapter,
+	})
+}
@@ -10,68 +10,32 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::{html_slug, last_segment},
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://www.gufengmh.com";
 const IMG_URL: &str = "https://res1.xiaoqinre.com";
 
-const FILTER_GENRE: [&str; 5] = ["", "shaonian", "shaonv", "qingnian", "zhenrenmanhua"];
-const FILTER_REGION: [&str; 6] = [
-	"",
-	"ribenmanhua",
-	"guochanmanhua",
-	"gangtaimanhua",
-	"oumeimanhua",
-	"hanguomanhua",
-];
-const FILTER_STATUS: [&str; 3] = ["", "wanjie", "lianzai"];
-const FILTER_SORT: [&str; 3] = ["post", "update", "click"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut genre = String::new();
-	let mut region = String::new();
-	let mut status = String::new();
-	let mut sort = String::from("click");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						genre = FILTER_GENRE[index].to_string();
-					}
-					"地区" => {
-						region = FILTER_REGION[index].to_string();
-					}
-					"进度" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				let ascending = value.get("ascending").as_bool().unwrap_or(false);
-				sort = FILTER_SORT[index].to_string();
-				if ascending {
-					sort = format!("-{}", sort)
-				}
-			}
-			_ => continue,
-		}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let genre = filters.get("类型");
+	let region = filters.get("地区");
+	let status = filters.get("进度");
+	let mut sort = filters.get("排序");
+	if filters.ascending() {
+		sort = format!("-{}", sort)
 	}
 
 	let url = if query.is_empty() {
@@ -96,16 +60,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".cover")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select(".cover").attr("href").read());
 		let cover = item.select(".cover>img").attr("src").read();
 		let title = item.select(".ell>a").text().read().trim().to_string();
 		mangas.push(Manga {
@@ -167,16 +122,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".cover")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select(".cover").attr("href").read());
 		let cover = item.select(".cover>img").attr("src").read();
 		let title = item.select(".ell>a").text().read().trim().to_string();
 		mangas.push(Manga {
@@ -255,15 +201,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = html_slug(item.attr("href").read());
 		let title = item.select("span").text().read();
 		let chapter = (index + 1) as f32;
 		let url = format!(
@@ -325,3 +263,14 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if kind == "manhua" => (id.clone(), None),
+		[kind, id, chapter] if kind == "manhua" => (id.clone(), Some(html_slug(chapter))),
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
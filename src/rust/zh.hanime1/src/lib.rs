@@ -9,10 +9,15 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	id::last_segment,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://hanime1.me";
 
@@ -44,14 +49,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let cover = item.select("img").attr("data-srcset").read();
 		let title = item.select("div>.comic-rows-videos-title").text().read();
 		mangas.push(Manga {
@@ -98,14 +96,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let cover = item.select("img").attr("data-srcset").read();
 		let title = item.select("div>.comic-rows-videos-title").text().read();
 		mangas.push(Manga {
@@ -215,3 +206,15 @@ fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Every comic is a single chapter, read at `/comic/<id>/<page>`.
+	match path_segments(&url).as_slice() {
+		[kind, id] if kind == "comic" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		[kind, id, _] if kind == "comic" => {
+			Ok(deep_link(get_manga_details(id.clone())?, Some(id.clone())))
+		}
+		_ => Err(unsupported()),
+	}
+}
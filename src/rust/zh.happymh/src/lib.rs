@@ -9,201 +9,33 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		ObjectRef, String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	request::user_agent,
+	url::path_segments,
+};
 
 fn get_url() -> String {
 	defaults_get("url").unwrap().as_string().unwrap().read()
 }
 
-fn user_agent() -> String {
-	defaults_get("User-Agent").unwrap().as_string().unwrap().read()
-}
-
-const FILTER_GENRE: [&str; 132] = [
-	"",
-	"rexue",
-	"gedou",
-	"wuxia",
-	"mohuan",
-	"mofa",
-	"maoxian",
-	"aiqing",
-	"gaoxiao",
-	"xiaoyuan",
-	"kehuan",
-	"hougong",
-	"lizhi",
-	"zhichang",
-	"meishi",
-	"shehui",
-	"heidao",
-	"zhanzheng",
-	"lishi",
-	"xuanyi",
-	"jingji",
-	"tiyu",
-	"kongbu",
-	"tuili",
-	"shenghuo",
-	"weiniang",
-	"zhiyu",
-	"shengui",
-	"sige",
-	"baihe",
-	"danmei",
-	"wudao",
-	"zhentan",
-	"zhainan",
-	"yinyue",
-	"mengxi",
-	"gufeng",
-	"lianai",
-	"dushi",
-	"xingzhuan",
-	"chuanyue",
-	"youxi",
-	"qita",
-	"aiqi",
-	"richang",
-	"fuhei",
-	"guzhuang",
-	"xianxia",
-	"shenghua",
-	"xiuxian",
-	"qinggan",
-	"gaibian",
-	"chunai",
-	"weimei",
-	"qiangwei",
-	"mingxing",
-	"lieqi",
-	"qingchun",
-	"huanxiang",
-	"jingqi",
-	"caihong",
-	"qiwen",
-	"quanmou",
-	"zhaidou",
-	"xianzhiji",
-	"zhuangbi",
-	"langman",
-	"ouxiang",
-	"danvzhu",
-	"fuchou",
-	"nuexin",
-	"egao",
-	"lingyi",
-	"jingxian",
-	"chongai",
-	"nixi",
-	"yaoguai",
-	"aimei",
-	"tongren",
-	"jiakong",
-	"zhenren",
-	"dongzuo",
-	"juwei",
-	"gongdou",
-	"naodong",
-	"mangai",
-	"zhandou",
-	"sangshi",
-	"meishaonv",
-	"guaiwu",
-	"xitong",
-	"zhidou",
-	"jijia",
-	"gaotian",
-	"jiangshi",
-	"zhiyu",
-	"dianjing",
-	"shenmo",
-	"yineng",
-	"mori",
-	"yinv",
-	"haokuai",
-	"qihuan",
-	"shenshi",
-	"zhengnengliang",
-	"gongting",
-	"qinqing",
-	"yangcheng",
-	"juqing",
-	"qingxiaoshuo",
-	"anhei",
-	"changtiao",
-	"xuanhuan",
-	"bazong",
-	"ouhuang",
-	"shengcun",
-	"yishijie",
-	"qita",
-	"C99",
-	"jiecao",
-	"AA",
-	"yingshihua",
-	"oufeng",
-	"nvshen",
-	"shuanggan",
-	"zhuansheng",
-	"yixing",
-	"fantaolu",
-	"shuangnanzhu",
-	"wudiliu",
-	"xingzhuanhuan",
-	"zhongsheng",
-];
-const FILTER_AREA: [&str; 7] = ["", "china", "japan", "hongkong", "europe", "korea", "other"];
-const FILTER_AUDIENCE: [&str; 6] = ["", "shaonian", "shaonv", "qingnian", "BL", "GL"];
-const FILTER_STATUS: [&str; 3] = ["-1", "0", "1"];
-const FILTER_ORDER: [&str; 2] = ["last_date", "views"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut genre = String::new();
-	let mut area = String::new();
-	let mut audience = String::new();
-	let mut status = String::from("-1");
-	let mut order = String::from("last_date");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						genre = FILTER_GENRE[index].to_string();
-					}
-					"地区" => {
-						area = FILTER_AREA[index].to_string();
-					}
-					"读者" => {
-						audience = FILTER_AUDIENCE[index].to_string();
-					}
-					"状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				order = FILTER_ORDER[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let genre = filters.get("类型");
+	let area = filters.get("地区");
+	let audience = filters.get("读者");
+	let status = filters.get("状态");
+	let order = filters.get("排序");
 
 	let base_url = get_url();
 
@@ -301,16 +133,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".manga-rank-cover>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select(".manga-rank-cover>a").attr("href").read());
 		let cover = item
 			.select(".manga-rank-cover>a>mip-img")
 			.attr("src")
@@ -481,3 +304,33 @@ fn modify_image_request(request: Request) {
 		.header("User-Agent", &ua)
 		.header("Origin", &base_url);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	match path_segments(&url).as_slice() {
+		[kind, id] if kind == "manga" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		[kind, chapter_id] if kind == "mangaread" => {
+			// The reader URL only carries the chapter code; the reading API
+			// names the manga it belongs to.
+			let base_url = get_url();
+			let url = format!(
+				"{}/v2.0/apis/manga/reading?code={}&v=v3.1818134",
+				base_url, chapter_id
+			);
+			let json = Request::new(url, HttpMethod::Get)
+				.header("Referer", &format!("{}/mangaread/{}", base_url, chapter_id))
+				.header("User-Agent", &user_agent())
+				.header("Origin", &base_url)
+				.header("X-Requested-With", "XMLHttpRequest")
+				.json()?;
+			let data = json.as_object()?;
+			let data = data.get("data").as_object()?;
+			let manga_id = data.get("manga_code").as_string()?.read();
+			Ok(deep_link(
+				get_manga_details(manga_id)?,
+				Some(chapter_id.clone()),
+			))
+		}
+		_ => Err(unsupported()),
+	}
+}
@@ -0,0 +1,105 @@
+use aidoku::{
+	harness::{filter, Harness},
+	std::String,
+	Listing,
+};
+use aidoku_happymh::{
+	get_chapter_list, get_manga_details, get_manga_list, get_manga_listing, get_page_list,
+	handle_url,
+};
+
+fn harness() -> Harness {
+	Harness::new(env!("CARGO_MANIFEST_DIR")).fixtures("tests/fixtures")
+}
+
+#[test]
+fn manga_list() {
+	let harness = harness();
+	let filters = vec![
+		filter::select("类型", 1),
+		filter::select("地区", 2),
+		filter::select("状态", 1),
+		filter::sort("排序", 1, false),
+	];
+	let result = get_manga_list(filters, 1).unwrap();
+	harness.golden("manga_list", &result);
+}
+
+#[test]
+fn search() {
+	let harness = harness();
+	let result = get_manga_list(vec![filter::title("一拳")], 1).unwrap();
+	harness.golden("search", &result);
+
+	let request = &harness.requests()[0];
+	assert_eq!(
+		request.header("Content-Type"),
+		Some("application/x-www-form-urlencoded")
+	);
+	assert_eq!(request.body_string(), "searchkey=一拳&v=v2.13");
+}
+
+#[test]
+fn rank_listing() {
+	let harness = harness();
+	let listing = Listing {
+		name: String::from("日阅读"),
+	};
+	let result = get_manga_listing(listing, 1).unwrap();
+	harness.golden("rank_listing", &result);
+}
+
+#[test]
+fn manga_details() {
+	let harness = harness();
+	let manga = get_manga_details(String::from("yiquanchaoren")).unwrap();
+	harness.golden("manga_details", &manga);
+}
+
+#[test]
+fn chapter_list() {
+	let harness = harness();
+	let chapters = get_chapter_list(String::from("yiquanchaoren")).unwrap();
+	harness.golden("chapter_list", &chapters);
+}
+
+#[test]
+fn page_list() {
+	let harness = harness();
+	let pages = get_page_list(
+		String::from("yiquanchaoren"),
+		String::from("yiquanchaoren-001"),
+	)
+	.unwrap();
+	harness.golden("page_list", &pages);
+}
+
+#[test]
+fn uses_configured_site_and_user_agent() {
+	let harness = harness()
+		.default("url", "https://m.happymh.com")
+		.default("User-Agent", "Mozilla/5.0 Test");
+	get_manga_details(String::from("yiquanchaoren")).unwrap();
+
+	let request = &harness.requests()[0];
+	assert_eq!(request.header("User-Agent"), Some("Mozilla/5.0 Test"));
+	assert_eq!(request.header("Origin"), Some("https://m.happymh.com"));
+}
+
+#[test]
+fn deep_links() {
+	let _harness = harness();
+
+	let link = handle_url(String::from("https://m.happymh.com/manga/yiquanchaoren")).unwrap();
+	assert_eq!(link.manga.unwrap().id, "yiquanchaoren");
+	assert!(link.chapter.is_none());
+
+	let link = handle_url(String::from(
+		"https://m.happymh.com/mangaread/yiquanchaoren-001",
+	))
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "yiquanchaoren");
+	assert_eq!(link.chapter.unwrap().id, "yiquanchaoren-001");
+
+	assert!(handle_url(String::from("https://m.happymh.com/rank/day")).is_err());
+}
@@ -10,80 +10,28 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://www.ho5ho.com";
 const MANGA_URL: &str = "https://www.ho5ho.com/%E4%B8%AD%E5%AD%97h%E6%BC%AB";
 
-const FILTER_CATEGORY: [&str; 31] = [
-	"",
-	"m女",
-	"m男",
-	"ntr",
-	"亂倫",
-	"催眠",
-	"全彩",
-	"可愛",
-	"同人",
-	"多p",
-	"女同",
-	"女性向",
-	"姐姐",
-	"學生",
-	"巨乳",
-	"幻想",
-	"強姦",
-	"懷孕",
-	"接吻",
-	"正太",
-	"母子",
-	"母狗",
-	"深喉",
-	"熟女",
-	"痴女",
-	"聖水",
-	"肉感",
-	"肛交",
-	"變態",
-	"貧乳",
-	"韓漫",
-];
-const FILTER_SORT: [&str; 3] = ["latest", "rating", "views"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-	let mut sort = String::from("latest");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类别" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				sort = FILTER_SORT[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let category = filters.get("类别");
+	let sort = filters.get("排序");
 
 	let url = if query.is_empty() {
 		if category.is_empty() {
@@ -210,13 +158,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Err(_) => continue,
 		};
 		let url = item.attr("href").read();
-		let id = url
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(&url);
 		let title = item.text().read();
 		let chapter = (index + 1) as f32;
 		chapters.push(Chapter {
@@ -261,3 +203,17 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let category = last_segment(MANGA_URL);
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if *kind == category || kind == "中字h漫" => (id.clone(), None),
+		[kind, id, chapter_id] if *kind == category || kind == "中字h漫" => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
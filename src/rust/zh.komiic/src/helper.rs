@@ -1,16 +1,30 @@
 use aidoku::{
+	error::Result,
 	helpers::substring::Substring,
 	prelude::*,
 	std::{
-		defaults::defaults_get,
+		current_date,
+		defaults::{defaults_get, defaults_set},
 		net::{HttpMethod, Request},
-		ObjectRef, String,
+		ObjectRef, String, StringRef, Vec,
 	},
+	MangaViewer,
 };
 use alloc::string::ToString;
+use zh_common::{
+	error::{check_response, SourceError},
+	json::json,
+	token::is_expired,
+};
+
+use crate::{
+	parser::{self, ImageLimit},
+	query::Query,
+};
 
 const WWW_URL: &str = "https://komiic.com";
 const API_URL: &str = "https://komiic.com/api/query";
+const LOGIN_URL: &str = "https://komiic.com/api/login";
 
 pub fn gen_manga_url(id: String) -> String {
 	format!("{}/comic/{}", WWW_URL, id)
@@ -46,148 +60,202 @@ pub fn gen_referer(image_url: String) -> String {
 	}
 }
 
-pub fn get_json(body: String) -> ObjectRef {
-	let mut request = Request::new(API_URL, HttpMethod::Post)
+pub fn get_json(query: Query) -> Result<ObjectRef> {
+	let body = query.body();
+	let request = gen_request(&body, session().ok());
+
+	request.send();
+
+	// A token revoked before its `exp`, such as by a password change, is
+	// answered with 401. Without an account that can log in, the query is
+	// sent again without a token.
+	let request = if request.status_code() == 401 {
+		defaults_set("token", StringRef::from("").0);
+		defaults_set("image_limit", StringRef::from("").0);
+		let request = gen_request(&body, login().ok());
+		request.send();
+		request
+	} else {
+		request
+	};
+	check_response(&request)?;
+
+	request.json()?.as_object()
+}
+
+fn gen_request(body: &str, token: Option<String>) -> Request {
+	let request = Request::new(API_URL, HttpMethod::Post)
 		.body(body.as_bytes())
 		.header("Content-Type", "application/json");
+	match token {
+		Some(token) => request.header("Authorization", &format!("Bearer {}", token)),
+		None => request,
+	}
+}
+
+fn get_default(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.unwrap_or_default()
+}
 
-	let cookie = defaults_get("cookie")
-        .and_then(|v| v.as_string())
-        .map(|s| s.read())
-        .unwrap_or_default();
-	if !cookie.is_empty() {
-		request = request.header("Cookie", &cookie);
+/// How many manga the guessed viewers are kept for.
+const VIEWERS_KEPT: usize = 100;
+
+/// The guessed viewers, kept as `viewers` in `{id}:{viewer}` pairs, the
+/// latest guessed last.
+fn viewers() -> Vec<(String, String)> {
+	get_default("viewers")
+		.split(',')
+		.filter_map(|pair| {
+			let (id, viewer) = pair.split_once(':')?;
+			Some((String::from(id), String::from(viewer)))
+		})
+		.collect()
+}
+
+/// The viewer guessed when a chapter of the manga was last read.
+pub fn get_viewer(id: &str) -> Option<MangaViewer> {
+	let viewers = viewers();
+	match viewers.iter().find(|(key, _)| key == id)?.1.as_str() {
+		"scroll" => Some(MangaViewer::Scroll),
+		"rtl" => Some(MangaViewer::Rtl),
+		_ => None,
 	}
+}
 
-	request
-		.json()
-		.unwrap()
-		.as_object()
-		.unwrap()
-}
-
-pub fn gen_category_body_string(
-	category: String,
-	status: String,
-	order_by: String,
-	page: i32,
-) -> String {
-	let category_id = if category.is_empty() {
-		"[]".to_string()
-	} else {
-		format!(r#"["{}"]"#, category)
+/// Keeps the viewer guessed for a manga, dropping the one guessed longest
+/// ago once more than [`VIEWERS_KEPT`] are kept.
+pub fn set_viewer(id: &str, viewer: MangaViewer) {
+	let value = match viewer {
+		MangaViewer::Scroll => "scroll",
+		_ => "rtl",
 	};
-	format!(
-		r#"{{
-      "operationName": "comicByCategories",
-      "query": "query comicByCategories($categoryId: [ID!]!, $pagination: Pagination!) {{\n  comicByCategories(categoryId: $categoryId, pagination: $pagination) {{\n    id\n    title\n    status\n    year\n    imageUrl\n    authors {{\n      id\n      name\n      __typename\n    }}\n    categories {{\n      id\n      name\n      __typename\n    }}\n    dateUpdated\n    monthViews\n    views\n    favoriteCount\n    lastBookUpdate\n    lastChapterUpdate\n    __typename\n  }}\n}}\n",
-      "variables": {{
-        "categoryId": {},
-        "pagination": {{
-            "asc": false,
-            "limit": {},
-            "offset": {},
-            "orderBy": "{}",
-						"status": "{}"
-        }}
-      }}
-    }}"#,
-		category_id,
-		20,
-		(page - 1) * 20,
-		order_by,
-		status,
-	)
+	let mut viewers = viewers();
+	viewers.retain(|(key, _)| key != id);
+	viewers.push((String::from(id), String::from(value)));
+	let start = viewers.len().saturating_sub(VIEWERS_KEPT);
+	let value = viewers[start..]
+		.iter()
+		.map(|(id, viewer)| format!("{}:{}", id, viewer))
+		.collect::<Vec<String>>()
+		.join(",");
+	defaults_set("viewers", StringRef::from(value).0);
 }
 
-pub fn gen_recent_update_body_string(page: i32) -> String {
-	format!(
-		r#"{{
-      "operationName": "recentUpdate",
-    	"query": "query recentUpdate($pagination: Pagination!) {{\n  recentUpdate(pagination: $pagination) {{\n    id\n    title\n    status\n    year\n    imageUrl\n    authors {{\n      id\n      name\n      __typename\n    }}\n    categories {{\n      id\n      name\n      __typename\n    }}\n    dateUpdated\n    monthViews\n    views\n    favoriteCount\n    lastBookUpdate\n    lastChapterUpdate\n    __typename\n  }}\n}}\n",
-    	"variables": {{
-        "pagination": {{
-            "asc": true,
-            "limit": {},
-            "offset": {},
-            "orderBy": "DATE_UPDATED"
-        }}
-    	}}
-    }}"#,
-		20,
-		(page - 1) * 20
-	)
+/// The daily image quota, kept as `image_limit` until it resets. Opening a
+/// chapter adds its pages to the usage kept here, so the API is only asked
+/// again once the quota has reset or looks used up.
+pub fn image_limit() -> Result<ImageLimit> {
+	let now = current_date() as i64;
+	if let Some(limit) = cached_image_limit(now) {
+		if limit.remaining() > 0 {
+			return Ok(limit);
+		}
+	}
+
+	let json = get_json(Query::image_limit())?;
+	let limit = parser::parse_image_limit(json.get("data").as_object()?)?;
+	set_image_limit(&limit, now);
+
+	Ok(limit)
 }
 
-pub fn gen_hot_body_string(order_by: String, page: i32) -> String {
-	format!(
-		r#"{{
-      "operationName": "hotComics",
-    	"query": "query hotComics($pagination: Pagination!) {{\n  hotComics(pagination: $pagination) {{\n    id\n    title\n    status\n    year\n    imageUrl\n    authors {{\n      id\n      name\n      __typename\n    }}\n    categories {{\n      id\n      name\n      __typename\n    }}\n    dateUpdated\n    monthViews\n    views\n    favoriteCount\n    lastBookUpdate\n    lastChapterUpdate\n    __typename\n  }}\n}}\n",
-    	"variables": {{
-        "pagination": {{
-            "asc": true,
-            "limit": {},
-            "offset": {},
-            "orderBy": "{}",
-            "status": ""
-        }}
-    	}}
-    }}"#,
-		20,
-		(page - 1) * 20,
-		order_by,
-	)
+/// Counts `count` more images against the kept quota.
+pub fn use_images(count: i32) {
+	let now = current_date() as i64;
+	if let Some(mut limit) = cached_image_limit(now) {
+		limit.usage += count;
+		set_image_limit(&limit, now);
+	}
 }
 
-pub fn gen_search_body_string(query: String) -> String {
-	format!(
-		r#"{{
-      "operationName": "searchComicAndAuthorQuery",
-      "query": "query searchComicAndAuthorQuery($keyword: String!) {{\n  searchComicsAndAuthors(keyword: $keyword) {{\n    comics {{\n      id\n      title\n      status\n      year\n      imageUrl\n      authors {{\n        id\n        name\n        __typename\n      }}\n      categories {{\n        id\n        name\n        __typename\n      }}\n      dateUpdated\n      monthViews\n      views\n      favoriteCount\n      lastBookUpdate\n      lastChapterUpdate\n      __typename\n    }}\n    authors {{\n      id\n      name\n      chName\n      enName\n      wikiLink\n      comicCount\n      views\n      __typename\n    }}\n    __typename\n  }}\n}}\n",
-      "variables": {{
-        "keyword": "{}"
-      }}
-    }}"#,
-		query,
-	)
+/// The kept quota as `{limit},{usage},{reset time}`, unless it has reset.
+fn cached_image_limit(now: i64) -> Option<ImageLimit> {
+	let value = get_default("image_limit");
+	let mut values = value.split(',').map(|value| value.parse::<i64>().ok());
+	let limit = values.next()??;
+	let usage = values.next()??;
+	let resets_at = values.next()??;
+
+	(resets_at > now).then_some(ImageLimit {
+		limit: limit as i32,
+		usage: usage as i32,
+		reset_in: (resets_at - now) as i32,
+	})
 }
 
-pub fn gen_id_body_string(id: String) -> String {
-	format!(
-		r#"{{
-      "operationName": "comicById",
-      "query": "query comicById($comicId: ID!) {{\n  comicById(comicId: $comicId) {{\n    id\n    title\n    status\n    year\n    imageUrl\n    authors {{\n      id\n      name\n      __typename\n    }}\n    categories {{\n      id\n      name\n      __typename\n    }}\n    dateCreated\n    dateUpdated\n    views\n    favoriteCount\n    lastBookUpdate\n    lastChapterUpdate\n    __typename\n  }}\n}}\n",
-      "variables": {{
-        "comicId": "{}"
-      }}
-    }}"#,
-		id
-	)
+fn set_image_limit(limit: &ImageLimit, now: i64) {
+	let value = format!(
+		"{},{},{}",
+		limit.limit,
+		limit.usage,
+		now + limit.reset_in as i64
+	);
+	defaults_set("image_limit", StringRef::from(value).0);
 }
 
-pub fn gen_chapter_body_string(id: String) -> String {
-	format!(
-		r#"{{
-      "operationName": "chapterByComicId",
-      "query": "query chapterByComicId($comicId: ID!) {{\n  chaptersByComicId(comicId: $comicId) {{\n    id\n    serial\n    type\n    dateCreated\n    dateUpdated\n    size\n    __typename\n  }}\n}}\n",
-      "variables": {{
-        "comicId": "{}"
-      }}
-    }}"#,
-		id
-	)
+/// The saved token while it is valid. It never logs in, so it is cheap
+/// enough for every image request.
+pub fn token() -> Option<String> {
+	let token = get_default("token");
+	(!token.is_empty() && !is_expired(&token)).then_some(token)
 }
 
-pub fn gen_images_body_string(id: String) -> String {
-	format!(
-		r#"{{
-      "operationName": "imagesByChapterId",
-      "query": "query imagesByChapterId($chapterId: ID!) {{\n  imagesByChapterId(chapterId: $chapterId) {{\n    id\n    kid\n    height\n    width\n    __typename\n  }}\n}}\n",
-      "variables": {{
-        "chapterId": "{}"
-      }}
-    }}"#,
-		id
-	)
+/// The saved token, else a new one from the account in the settings. Reading
+/// works without an account, only with a lower daily image quota.
+pub fn session() -> Result<String> {
+	match token() {
+		Some(token) => Ok(token),
+		None => login(),
+	}
+}
+
+/// Logs in and saves the token. The MD5 of credentials the site refused is
+/// kept as `failed_login`, so they are not sent again until the username or
+/// password changes.
+fn login() -> Result<String> {
+	let username = get_default("username");
+	let password = get_default("password");
+
+	if username.is_empty() || password.is_empty() {
+		return Err(SourceError::Auth.into());
+	}
+	let credentials = format!("{:x}", md5::compute(format!("{}\n{}", username, password)));
+	if get_default("failed_login") == credentials {
+		return Err(SourceError::Auth.into());
+	}
+
+	let body = json!({
+		"email": username,
+		"password": password,
+	})
+	.to_string();
+
+	let request = Request::new(LOGIN_URL, HttpMethod::Post)
+		.body(body.as_bytes())
+		.header("Content-Type", "application/json");
+
+	request.send();
+	check_response(&request)?;
+
+	// A wrong email or password is answered with 400.
+	if request.status_code() != 200 {
+		defaults_set("failed_login", StringRef::from(credentials).0);
+		return Err(SourceError::Auth.into());
+	}
+
+	let token = request
+		.json()
+		.and_then(|json| json.as_object())
+		.map(|json| json.get("token"))
+		.map_err(|_| SourceError::Redesign)?;
+	let value = token.as_string().map_err(|_| SourceError::Redesign)?.read();
+
+	defaults_set("token", token);
+	// The quota belongs to the account, so the one kept is not this one's.
+	defaults_set("image_limit", StringRef::from("").0);
+
+	Ok(value)
 }
@@ -4,83 +4,108 @@ extern crate alloc;
 use aidoku::{
 	error::Result,
 	prelude::*,
-	std::{defaults::defaults_get, net::Request, String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, Listing, Manga, MangaPageResult, Page,
 };
-use alloc::string::ToString;
 
 mod helper;
 mod parser;
+mod query;
 
-const FILTER_CATEGORY: [&str; 38] = [
-	"", "1", "3", "4", "5", "6", "7", "8", "10", "11", "2", "12", "13", "14", "15", "16", "17",
-	"18", "19", "20", "21", "22", "23", "24", "25", "26", "27", "9", "28", "31", "32", "33", "34",
-	"35", "36", "37", "40", "42",
-];
-const FILTER_STATUS: [&str; 3] = ["", "ONGOING", "END"];
-const FILTER_ORDER_BY: [&str; 3] = ["DATE_UPDATED", "VIEWS", "FAVORITE_COUNT"];
+use query::{Query, PAGE_SIZE};
+use zh_common::{
+	filter::FilterValues,
+	json::parse_list,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
+
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-	let mut status = String::new();
-	let mut order_by = String::new();
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let author = filters.author();
+	let category = filters.get("类型");
+	let status = filters.get("状态");
+	let order_by = filters.get("排序");
 
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					"状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				order_by = FILTER_ORDER_BY[index].to_string();
-			}
-			_ => continue,
-		}
+	if !query.is_empty() || !author.is_empty() {
+		return search(query, author, page);
 	}
 
-	let body = if query.is_empty() {
-		helper::gen_category_body_string(category, status, order_by, page)
-	} else {
-		helper::gen_search_body_string(query.clone())
-	};
-
-	let json = helper::get_json(body.clone());
+	let json = helper::get_json(Query::comic_by_categories(category, status, order_by, page))?;
 	let data = json.get("data").as_object()?;
-	let mangas;
+	let list = data.get("comicByCategories").as_array()?;
+
+	Ok(MangaPageResult {
+		manga: parse_list(list, parser::parse_manga),
+		has_more: true,
+	})
+}
 
-	if query.is_empty() {
-		let list = data.get("comicByCategories").as_array()?;
-		mangas = parser::parse_manga_list(list);
+/// Searches comics, or lists the comics of every author tapped in the
+/// details. Neither query is paged, so every result is on the first page.
+fn search(query: String, author: String, page: i32) -> Result<MangaPageResult> {
+	if page > 1 {
+		return Ok(MangaPageResult {
+			manga: Vec::new(),
+			has_more: false,
+		});
+	}
+
+	let mangas = if query.is_empty() {
+		search_authors(author)?
 	} else {
+		let json = helper::get_json(Query::search(query))?;
+		let data = json.get("data").as_object()?;
 		let data = data.get("searchComicsAndAuthors").as_object()?;
-		let list = data.get("comics").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		parse_list(data.get("comics").as_array()?, parser::parse_manga)
 	};
 
 	Ok(MangaPageResult {
 		manga: mangas,
-		has_more: query.is_empty(),
+		has_more: false,
 	})
 }
 
+/// The comics of each author named in `author`, which joins every author of
+/// a comic. Search also matches longer names, so only exact ones are kept.
+fn search_authors(author: String) -> Result<Vec<Manga>> {
+	let mut author_ids: Vec<String> = Vec::new();
+	for name in author.split(", ").filter(|name| !name.is_empty()) {
+		let json = helper::get_json(Query::search(String::from(name)))?;
+		let data = json.get("data").as_object()?;
+		let data = data.get("searchComicsAndAuthors").as_object()?;
+		let ids = data.get("authors").as_array()?.filter_map(|author| {
+			let author = author.as_object().ok()?;
+			if author.get("name").as_string().ok()?.read() != name {
+				return None;
+			}
+			Some(author.get("id").as_string().ok()?.read())
+		});
+		for id in ids {
+			if !author_ids.contains(&id) {
+				author_ids.push(id);
+			}
+		}
+	}
+
+	let mut mangas: Vec<Manga> = Vec::new();
+	for author_id in author_ids {
+		let json = helper::get_json(Query::comics_by_author(author_id))?;
+		let data = json.get("data").as_object()?;
+		for manga in parse_list(data.get("comicsByAuthor").as_array()?, parser::parse_manga) {
+			if !mangas.iter().any(|m| m.id == manga.id) {
+				mangas.push(manga);
+			}
+		}
+	}
+
+	Ok(mangas)
+}
+
 #[get_manga_listing]
 fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let mut is_recent_update = false;
@@ -96,25 +121,28 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"历史热门" => {
 			order_by = String::from("VIEWS");
 		}
+		"我的收藏" | "阅读历史" | "我的书单" => {
+			return get_account_listing(listing.name.as_str(), page)
+		}
 		_ => return get_manga_list(Vec::new(), page),
 	}
 
 	let body = if is_recent_update {
-		helper::gen_recent_update_body_string(page)
+		Query::recent_update(page)
 	} else {
-		helper::gen_hot_body_string(order_by, page)
+		Query::hot_comics(order_by, page)
 	};
 
-	let json = helper::get_json(body);
+	let json = helper::get_json(body)?;
 	let data = json.get("data").as_object()?;
 	let mangas;
 
 	if is_recent_update {
 		let list = data.get("recentUpdate").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parse_list(list, parser::parse_manga);
 	} else {
 		let list = data.get("hotComics").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parse_list(list, parser::parse_manga);
 	};
 
 	Ok(MangaPageResult {
@@ -123,20 +151,110 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	})
 }
 
+/// Lists the favourites, reading history or folders of the logged in account.
+/// These only hold comic ids, so the comics are then loaded in one query.
+fn get_account_listing(name: &str, page: i32) -> Result<MangaPageResult> {
+	helper::session()?;
+
+	let (ids, has_more) = match name {
+		"我的收藏" => {
+			let json = helper::get_json(Query::favorites(page))?;
+			let data = json.get("data").as_object()?;
+			let ids = parser::parse_comic_ids(data.get("favorites").as_array()?);
+			let has_more = ids.len() as i32 >= PAGE_SIZE;
+			(ids, has_more)
+		}
+		"阅读历史" => {
+			let json = helper::get_json(Query::read_history(page))?;
+			let data = json.get("data").as_object()?;
+			let ids = parser::parse_comic_ids(data.get("readComicHistory").as_array()?);
+			let has_more = ids.len() as i32 >= PAGE_SIZE;
+			(ids, has_more)
+		}
+		_ => get_folder_comic_ids(page)?,
+	};
+	if ids.is_empty() {
+		return Ok(MangaPageResult {
+			manga: Vec::new(),
+			has_more: false,
+		});
+	}
+
+	let json = helper::get_json(Query::comic_by_ids(ids.clone()))?;
+	let data = json.get("data").as_object()?;
+	let mut mangas = parse_list(data.get("comicByIds").as_array()?, parser::parse_manga);
+	mangas.sort_by_key(|manga| ids.iter().position(|id| *id == manga.id));
+
+	Ok(MangaPageResult {
+		manga: mangas,
+		has_more,
+	})
+}
+
+/// Pages through the folders one after another, as if they were one list in
+/// folder order. A page that spans two folders takes the rest of the first
+/// and the start of the next.
+fn get_folder_comic_ids(page: i32) -> Result<(Vec<String>, bool)> {
+	let json = helper::get_json(Query::my_folders())?;
+	let data = json.get("data").as_object()?;
+	let folders = data
+		.get("myFolders")
+		.as_array()?
+		.filter_map(|folder| {
+			let folder = folder.as_object().ok()?;
+			let id = folder.get("id").as_string().ok()?.read();
+			let count = folder.get("comicCount").as_int().ok()? as i32;
+			Some((id, count))
+		})
+		.collect::<Vec<(String, i32)>>();
+
+	let mut offset = (page - 1) * PAGE_SIZE;
+	let mut ids: Vec<String> = Vec::new();
+	let mut taken = 0;
+	for (folder, count) in &folders {
+		if offset >= *count {
+			offset -= count;
+			continue;
+		}
+		let limit = (PAGE_SIZE - taken).min(count - offset);
+		let json = helper::get_json(Query::folder_comic_ids(folder.clone(), offset, limit))?;
+		let data = json.get("data").as_object()?;
+		let data = data.get("folderComicIds").as_object()?;
+		for id in parser::parse_comic_ids(data.get("comicIds").as_array()?) {
+			if !ids.contains(&id) {
+				ids.push(id);
+			}
+		}
+		taken += limit;
+		offset = 0;
+		if taken == PAGE_SIZE {
+			break;
+		}
+	}
+	let total = folders.iter().map(|(_, count)| count).sum::<i32>();
+
+	Ok((ids, total > page * PAGE_SIZE))
+}
+
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let body = helper::gen_id_body_string(id);
-	let json = helper::get_json(body);
+	let json = helper::get_json(Query::comic_by_id(id))?;
 	let data = json.get("data").as_object()?;
 	let data = data.get("comicById").as_object()?;
+	let mut manga = parser::parse_manga(data)?;
 
-	Ok(parser::parse_manga(data))
+	// Komiic does not tell webtoons apart, so the viewer is the one guessed
+	// from the image sizes when a chapter was last read, else right to left.
+	if let Some(viewer) = helper::get_viewer(&manga.id) {
+		manga.viewer = viewer;
+	}
+
+	Ok(manga)
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let body = helper::gen_chapter_body_string(id.clone());
-	let json = helper::get_json(body);
+	let json = helper::get_json(Query::chapters_by_comic_id(id.clone()))?;
 	let data = json.get("data").as_object()?;
 	let list = data.get("chaptersByComicId").as_array()?;
 
@@ -145,29 +263,52 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
-	let body = helper::gen_images_body_string(chapter_id.clone());
-	let json = helper::get_json(body);
+	// Images past the daily quota fail without a reason, so a used up quota
+	// is shown as a text page instead. Reading goes on if the query fails.
+	let limit = helper::image_limit().ok();
+	if let Some(limit) = limit {
+		if limit.remaining() == 0 {
+			return Ok(Vec::from([limit.error().page(0)]));
+		}
+	}
+
+	let json = helper::get_json(Query::images_by_chapter_id(chapter_id.clone()))?;
 	let data = json.get("data").as_object()?;
 	let list = data.get("imagesByChapterId").as_array()?;
 
-	Ok(parser::parse_page_list(manga_id, chapter_id, list))
+	// The sizes come with the images, so the viewer is guessed again each time
+	// a chapter is read, for the details to show next.
+	let sizes = parser::parse_image_sizes(list.clone());
+	if !sizes.is_empty() {
+		helper::set_viewer(&manga_id, parser::guess_viewer(&sizes));
+	}
+
+	let pages = parser::parse_page_list(manga_id, chapter_id, list);
+	helper::use_images(pages.len() as i32);
+
+	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) -> Request {
-    let url = request.url().read();
+	let url = request.url().read();
+	let request = request.header("Referer", &helper::gen_referer(url));
 
-    let cookie = defaults_get("cookie")
-        .and_then(|v| v.as_string())
-        .map(|s| s.read())
-        .unwrap_or_default();
+	match helper::token() {
+		Some(token) => request.header("Authorization", &format!("Bearer {}", token)),
+		None => request,
+	}
+}
 
-    let request = request
-        .header("Referer", &helper::gen_referer(url));
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		[kind, id, chapter, chapter_id, ..] if kind == "comic" && chapter == "chapter" => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		_ => return Err(unsupported()),
+	};
 
-    if cookie.is_empty() {
-        request
-    } else {
-        request.header("Cookie", &cookie)
-    }
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
 }
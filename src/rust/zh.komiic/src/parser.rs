@@ -1,61 +1,43 @@
 use crate::helper;
 use aidoku::{
-	std::{ArrayRef, ObjectRef, String, Vec},
+	error::Result,
+	std::{ArrayRef, ObjectRef, String, ValueRef, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
 
 use alloc::string::ToString;
+use zh_common::error::SourceError;
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
-	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
-}
-
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let id = manga.get("id").as_string().unwrap().read();
-	let cover = manga.get("imageUrl").as_string().unwrap().read();
-	let title = manga.get("title").as_string().unwrap().read();
-	let author = manga
-		.get("authors")
-		.as_array()
-		.unwrap()
-		.map(|author| {
-			author
-				.as_object()
-				.unwrap()
-				.get("name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>()
-		.join(", ");
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let id = manga.get("id").as_string()?.read();
+	let cover = manga.get("imageUrl").as_string().unwrap_or_default().read();
+	let title = manga.get("title").as_string()?.read();
+	let author = names(manga.get("authors")).join(", ");
 	let artist = String::new();
-	let description = String::new();
+	// Only `comicById` selects the description.
+	let description = manga
+		.get("description")
+		.as_string()
+		.unwrap_or_default()
+		.read()
+		.trim()
+		.to_string();
 	let url = helper::gen_manga_url(id.clone());
-	let categories = manga
-		.get("categories")
-		.as_array()
-		.unwrap()
-		.map(|category| {
-			category
-				.as_object()
-				.unwrap()
-				.get("name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>();
-	let status = match manga.get("status").as_string().unwrap().read().as_str() {
+	let categories = names(manga.get("categories"));
+	let status = match manga
+		.get("status")
+		.as_string()
+		.unwrap_or_default()
+		.read()
+		.as_str()
+	{
 		"ONGOING" => MangaStatus::Ongoing,
 		"END" => MangaStatus::Completed,
 		_ => MangaStatus::Unknown,
 	};
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -67,7 +49,15 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
+}
+
+/// The `name` of each object in an array such as `authors`.
+fn names(list: ValueRef) -> Vec<String> {
+	list.as_array()
+		.unwrap_or_default()
+		.filter_map(|item| Some(item.as_object().ok()?.get("name").as_string().ok()?.read()))
+		.collect::<Vec<String>>()
 }
 
 pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Vec<Chapter> {
@@ -75,34 +65,13 @@ pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Vec<Chapt
 	let mut chapters: Vec<Chapter> = Vec::new();
 
 	for item in chapter_list {
-		let chapter = item.as_object().unwrap();
-		let id = chapter.get("id").as_string().unwrap().read();
-		let serial = chapter.get("serial").as_string().unwrap().read();
-		let chapter_type = chapter.get("type").as_string().unwrap().read();
-		let url = helper::gen_chapter_url(manga_id.clone(), id.clone());
-
-		if chapter_type.as_str() == "book" {
-			let volume_num = serial.parse::<f32>().unwrap_or(0.0);
-			volumes.push(Chapter {
-				id,
-				title: serial,
-				volume: volume_num,
-				chapter: -1.0,
-				scanlator: chapter_type.to_string(),
-				url,
-				..Default::default()
-			});
+		let Ok(chapter) = parse_chapter(&manga_id, item) else {
+			continue;
+		};
+		if chapter.scanlator == "book" {
+			volumes.push(chapter);
 		} else {
-			let chapter_num = serial.parse::<f32>().unwrap_or(0.0);
-			chapters.push(Chapter {
-				id,
-				title: serial,
-				volume: -1.0,
-				chapter: chapter_num,
-				scanlator: chapter_type.to_string(),
-				url,
-				..Default::default()
-			});
+			chapters.push(chapter);
 		}
 	}
 
@@ -113,16 +82,43 @@ pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Vec<Chapt
 	all_chapters
 }
 
+fn parse_chapter(manga_id: &str, item: ValueRef) -> Result<Chapter> {
+	let chapter = item.as_object()?;
+	let id = chapter.get("id").as_string()?.read();
+	let serial = chapter.get("serial").as_string()?.read();
+	let chapter_type = chapter.get("type").as_string()?.read();
+	let url = helper::gen_chapter_url(manga_id.to_string(), id.clone());
+	let number = serial.parse::<f32>().unwrap_or(0.0);
+	let (volume, chapter) = if chapter_type.as_str() == "book" {
+		(number, -1.0)
+	} else {
+		(-1.0, number)
+	};
+
+	Ok(Chapter {
+		id,
+		title: serial,
+		volume,
+		chapter,
+		scanlator: chapter_type,
+		url,
+		..Default::default()
+	})
+}
+
 pub fn parse_page_list(manga_id: String, chapter_id: String, page_list: ArrayRef) -> Vec<Page> {
 	let mut pages: Vec<Page> = Vec::new();
 
-	for (index, item) in page_list.enumerate() {
-		let page = item.as_object().unwrap();
-		let index = index as i32;
-		let id = page.get("kid").as_string().unwrap().read();
-		let url = helper::gen_page_url(manga_id.clone(), chapter_id.clone(), id);
+	for item in page_list {
+		let Ok(id) = item
+			.as_object()
+			.and_then(|page| page.get("kid").as_string())
+		else {
+			continue;
+		};
+		let url = helper::gen_page_url(manga_id.clone(), chapter_id.clone(), id.read());
 		pages.push(Page {
-			index,
+			index: pages.len() as i32,
 			url,
 			..Default::default()
 		})
@@ -130,3 +126,89 @@ pub fn parse_page_list(manga_id: String, chapter_id: String, page_list: ArrayRef
 
 	pages
 }
+
+/// The size of a page image, as `imagesByChapterId` reports it. Aidoku pages
+/// have no room for it, so it is only used to pick the viewer.
+pub struct ImageSize {
+	pub width: i32,
+	pub height: i32,
+}
+
+impl ImageSize {
+	/// A strip at least twice as tall as it is wide, as webtoons are cut.
+	pub fn is_long_strip(&self) -> bool {
+		self.height >= self.width * 2
+	}
+
+	/// Two pages scanned side by side.
+	pub fn is_spread(&self) -> bool {
+		self.width > self.height
+	}
+}
+
+/// Reads the sizes of a page list, skipping the images without one.
+pub fn parse_image_sizes(page_list: ArrayRef) -> Vec<ImageSize> {
+	page_list
+		.filter_map(|item| {
+			let item = item.as_object().ok()?;
+			let width = item.get("width").as_int().ok()? as i32;
+			let height = item.get("height").as_int().ok()? as i32;
+			(width > 0 && height > 0).then_some(ImageSize { width, height })
+		})
+		.collect::<Vec<ImageSize>>()
+}
+
+/// Scroll for chapters made mostly of long strips, otherwise right to left,
+/// which also keeps double-page spreads on a page of their own.
+pub fn guess_viewer(sizes: &[ImageSize]) -> MangaViewer {
+	let strips = sizes.iter().filter(|size| size.is_long_strip()).count();
+	let spreads = sizes.iter().filter(|size| size.is_spread()).count();
+	if strips * 2 > sizes.len() && spreads == 0 {
+		MangaViewer::Scroll
+	} else {
+		MangaViewer::Rtl
+	}
+}
+
+/// Reads a list of comic ids, given either as strings or as objects with a
+/// `comicId`, as the favourites and the reading history are.
+pub fn parse_comic_ids(list: ArrayRef) -> Vec<String> {
+	list.filter_map(|item| {
+		let id = match item.as_object() {
+			Ok(item) => item.get("comicId").as_string().ok()?,
+			Err(_) => item.as_string().ok()?,
+		};
+		Some(id.read())
+	})
+	.collect::<Vec<String>>()
+}
+
+/// The `getImageLimit` of an account.
+pub struct ImageLimit {
+	pub limit: i32,
+	pub usage: i32,
+	pub reset_in: i32,
+}
+
+impl ImageLimit {
+	pub fn remaining(&self) -> i32 {
+		(self.limit - self.usage).max(0)
+	}
+
+	pub fn error(&self) -> SourceError {
+		SourceError::ImageQuota {
+			remaining: self.remaining(),
+			limit: self.limit,
+			reset_in: self.reset_in,
+		}
+	}
+}
+
+pub fn parse_image_limit(data: ObjectRef) -> Result<ImageLimit> {
+	let data = data.get("getImageLimit").as_object()?;
+	Ok(ImageLimit {
+		limit: data.get("limit").as_int()? as i32,
+		usage: data.get("usage").as_int()? as i32,
+		reset_in: data.get("resetInSeconds").as_int().unwrap_or_default() as i32,
+	})
+}
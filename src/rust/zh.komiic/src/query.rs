@@ -0,0 +1,227 @@
+use aidoku::{
+	prelude::*,
+	std::{String, Vec},
+};
+use alloc::string::ToString;
+use zh_common::json::{json, Value};
+
+pub const PAGE_SIZE: i32 = 20;
+
+const COMIC_FIELDS: &str = "fragment ComicFields on Comic { id title status year imageUrl authors { id name } categories { id name } dateCreated dateUpdated monthViews views favoriteCount lastBookUpdate lastChapterUpdate }";
+
+/// A GraphQL operation sent to the komiic API.
+pub struct Query {
+	name: &'static str,
+	params: &'static str,
+	selection: &'static str,
+	fragments: &'static [&'static str],
+	variables: Value,
+}
+
+impl Query {
+	pub fn comic_by_categories(
+		category: String,
+		status: String,
+		order_by: String,
+		page: i32,
+	) -> Self {
+		let category_id = if category.is_empty() {
+			json!([])
+		} else {
+			json!([category])
+		};
+		Self {
+			name: "comicByCategories",
+			params: "($categoryId: [ID!]!, $pagination: Pagination!)",
+			selection: "comicByCategories(categoryId: $categoryId, pagination: $pagination) { ...ComicFields }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({
+				"categoryId": category_id,
+				"pagination": pagination(page, false, &order_by, Some(&status)),
+			}),
+		}
+	}
+
+	pub fn recent_update(page: i32) -> Self {
+		Self {
+			name: "recentUpdate",
+			params: "($pagination: Pagination!)",
+			selection: "recentUpdate(pagination: $pagination) { ...ComicFields }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({
+				"pagination": pagination(page, true, "DATE_UPDATED", None),
+			}),
+		}
+	}
+
+	pub fn hot_comics(order_by: String, page: i32) -> Self {
+		Self {
+			name: "hotComics",
+			params: "($pagination: Pagination!)",
+			selection: "hotComics(pagination: $pagination) { ...ComicFields }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({
+				"pagination": pagination(page, true, &order_by, Some("")),
+			}),
+		}
+	}
+
+	pub fn search(keyword: String) -> Self {
+		Self {
+			name: "searchComicAndAuthorQuery",
+			params: "($keyword: String!)",
+			selection: "searchComicsAndAuthors(keyword: $keyword) { comics { ...ComicFields } authors { id name } }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({ "keyword": keyword }),
+		}
+	}
+
+	pub fn comic_by_id(id: String) -> Self {
+		Self {
+			name: "comicById",
+			params: "($comicId: ID!)",
+			selection: "comicById(comicId: $comicId) { ...ComicFields description }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({ "comicId": id }),
+		}
+	}
+
+	pub fn comics_by_author(author_id: String) -> Self {
+		Self {
+			name: "comicsByAuthor",
+			params: "($authorId: ID!)",
+			selection: "comicsByAuthor(authorId: $authorId) { ...ComicFields }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({ "authorId": author_id }),
+		}
+	}
+
+	pub fn chapters_by_comic_id(id: String) -> Self {
+		Self {
+			name: "chapterByComicId",
+			params: "($comicId: ID!)",
+			selection: "chaptersByComicId(comicId: $comicId) { id serial type dateCreated dateUpdated size }",
+			fragments: &[],
+			variables: json!({ "comicId": id }),
+		}
+	}
+
+	pub fn images_by_chapter_id(id: String) -> Self {
+		Self {
+			name: "imagesByChapterId",
+			params: "($chapterId: ID!)",
+			selection: "imagesByChapterId(chapterId: $chapterId) { id kid height width }",
+			fragments: &[],
+			variables: json!({ "chapterId": id }),
+		}
+	}
+
+	/// The ids of the logged in account's favourites, latest updated first.
+	pub fn favorites(page: i32) -> Self {
+		Self {
+			name: "favoritesQuery",
+			params: "($pagination: Pagination!)",
+			selection: "favorites(pagination: $pagination) { id comicId }",
+			fragments: &[],
+			variables: json!({
+				"pagination": pagination(page, false, "COMIC_DATE_UPDATED", None),
+			}),
+		}
+	}
+
+	/// The ids of the comics the logged in account read, latest first.
+	pub fn read_history(page: i32) -> Self {
+		Self {
+			name: "readComicHistory",
+			params: "($pagination: Pagination!)",
+			selection: "readComicHistory(pagination: $pagination) { id comicId }",
+			fragments: &[],
+			variables: json!({
+				"pagination": pagination(page, false, "DATE_UPDATED", None),
+			}),
+		}
+	}
+
+	/// The folders (书单) of the logged in account.
+	pub fn my_folders() -> Self {
+		Self {
+			name: "myFolders",
+			params: "",
+			selection: "myFolders { id key name comicCount }",
+			fragments: &[],
+			variables: json!({}),
+		}
+	}
+
+	pub fn folder_comic_ids(folder_id: String, offset: i32, limit: i32) -> Self {
+		Self {
+			name: "folderComicIds",
+			params: "($folderId: ID!, $pagination: Pagination!)",
+			selection: "folderComicIds(folderId: $folderId, pagination: $pagination) { folderId key comicIds }",
+			fragments: &[],
+			variables: json!({
+				"folderId": folder_id,
+				"pagination": {
+					"asc": false,
+					"limit": limit,
+					"offset": offset,
+					"orderBy": "DATE_UPDATED",
+				},
+			}),
+		}
+	}
+
+	pub fn comic_by_ids(ids: Vec<String>) -> Self {
+		Self {
+			name: "comicByIds",
+			params: "($comicIds: [ID]!)",
+			selection: "comicByIds(comicIds: $comicIds) { ...ComicFields }",
+			fragments: &[COMIC_FIELDS],
+			variables: json!({ "comicIds": ids }),
+		}
+	}
+
+	/// The daily image quota of the logged in account, or of the IP address
+	/// without one.
+	pub fn image_limit() -> Self {
+		Self {
+			name: "getImageLimit",
+			params: "",
+			selection: "getImageLimit { limit usage resetInSeconds }",
+			fragments: &[],
+			variables: json!({}),
+		}
+	}
+
+	/// The JSON request body: the operation name, the document with its
+	/// fragments appended, and the escaped variables.
+	pub fn body(&self) -> String {
+		let mut document = format!(
+			"query {}{} {{ {} }}",
+			self.name, self.params, self.selection
+		);
+		for fragment in self.fragments {
+			document.push('\n');
+			document.push_str(fragment);
+		}
+		json!({
+			"operationName": self.name,
+			"query": document,
+			"variables": self.variables,
+		})
+		.to_string()
+	}
+}
+
+fn pagination(page: i32, asc: bool, order_by: &str, status: Option<&str>) -> Value {
+	let mut pagination = json!({
+		"asc": asc,
+		"limit": PAGE_SIZE,
+		"offset": (page - 1) * PAGE_SIZE,
+		"orderBy": order_by,
+	});
+	if let Some(status) = status {
+		pagination["status"] = json!(status);
+	}
+	pagination
+}
@@ -1,16 +1,20 @@
 use aidoku::{
-	error::{AidokuError, AidokuErrorKind},
 	helpers::{substring::Substring, uri::encode_uri},
 	prelude::*,
 	std::{
 		current_date,
 		defaults::{defaults_get, defaults_set},
 		net::{HttpMethod, Request},
-		String, ValueRef,
+		String, StringRef, ValueRef, Vec,
 	},
 };
 use alloc::string::ToString;
 use md5::compute;
+use zh_common::{
+	error::{check_response, Error, SourceError},
+	json::json,
+	token::is_expired,
+};
 
 use crate::crypto;
 
@@ -34,16 +38,10 @@ pub fn gen_signature(url: &str, time: &str, nonce: &str, method: &str) -> String
 	crypto::encrypt(text.as_bytes(), KEY)
 }
 
-pub fn gen_request(url: String, method: HttpMethod) -> Request {
+pub fn gen_request(url: String, method: HttpMethod, authorization: &str) -> Request {
 	let time = gen_time();
 	let nonce = gen_nonce();
 	let signature = gen_signature(&url, &time, &nonce, &format!("{:?}", method));
-	let token = defaults_get("token").unwrap().as_string().unwrap().read();
-	let authorization = if !url.contains("sign-in") && token.is_empty() {
-		login().unwrap()
-	} else {
-		token
-	};
 	Request::new(url, method)
 		.header("api-key", API_KEY)
 		.header("app-build-version", "45")
@@ -51,75 +49,204 @@ pub fn gen_request(url: String, method: HttpMethod) -> Request {
 		.header("app-platform", "android")
 		.header("app-uuid", "defaultUuid")
 		.header("app-version", "2.2.1.3.3.4")
-		.header("image-quality", "original")
+		.header("image-quality", &image_quality())
 		.header("time", &time)
 		.header("nonce", &nonce)
 		.header("signature", &signature)
 		.header("Accept", "application/vnd.picacomic.com.v1+json")
-		.header("Authorization", &authorization)
+		.header("Authorization", authorization)
 		.header("Content-Type", "application/json; charset=UTF-8")
 		.header("User-Agent", "okhttp/3.8.1")
 }
 
-pub fn login() -> Result<String, AidokuError> {
-	let request = gen_request(gen_login_url(), HttpMethod::Post).header("Authorization", "");
-	let username = defaults_get("username")?.as_string()?.read();
-	let password = defaults_get("password")?.as_string()?.read();
+/// The token to send: the one entered in the settings unless the API
+/// rejected it, else the one from the last login, as long as it has not
+/// expired. Otherwise logs in.
+fn authorization() -> Result<String, Error> {
+	let token = get_default("token");
+	if !token.is_empty() && token != get_default("rejected_token") && !is_expired(&token) {
+		return Ok(token);
+	}
+	let session = get_default("session");
+	if !session.is_empty() && !is_expired(&session) {
+		return Ok(session);
+	}
+	login("")
+}
+
+/// Logs in with the username and password from the settings and keeps the
+/// token as `session`, leaving the `token` setting alone. A session that an
+/// earlier call got is reused, unless it is the `rejected` one.
+///
+/// The app calls into a source's wasm instance one call at a time, so two
+/// logins never overlap and no lock is needed around this.
+fn login(rejected: &str) -> Result<String, Error> {
+	let session = get_default("session");
+	if !session.is_empty() && session != rejected && !is_expired(&session) {
+		return Ok(session);
+	}
+
+	let username = get_default("username");
+	let password = get_default("password");
 
 	if username.is_empty() || password.is_empty() {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(SourceError::Auth.into());
 	}
 
-	let body = format!(
-		r#"{{
-			"email": "{}",
-			"password": "{}"
-		}}"#,
-		username, password
-	);
+	let body = json!({
+		"email": username,
+		"password": password,
+	})
+	.to_string();
 
-	let request = request.body(body.as_bytes());
+	let request = gen_request(gen_login_url(), HttpMethod::Post, "").body(body.as_bytes());
 
 	request.send();
+	check_response(&request)?;
 
+	// A wrong email or password is answered with 400.
 	if request.status_code() != 200 {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(SourceError::Auth.into());
 	}
 
-	let json = request.json()?;
-	let data = json.as_object()?;
-	let data = data.get("data").as_object()?;
-	let token = data.get("token");
+	let token = request
+		.json()
+		.and_then(|json| json.as_object())
+		.and_then(|json| json.get("data").as_object())
+		.map(|data| data.get("token"))
+		.map_err(|_| SourceError::Redesign)?;
+	let value = token.as_string().map_err(|_| SourceError::Redesign)?.read();
 
-	defaults_set("token", token.clone());
+	defaults_set("session", token);
 
-	Ok(token.as_string()?.read())
+	Ok(value)
 }
 
-pub fn search(keyword: String, page: i32) -> Result<ValueRef, AidokuError> {
-	let url = gen_search_url(page);
-	let body = format!(
-		r#"{{
-			"keyword": "{}",
-			"sort": "dd"
-		}}"#,
-		keyword,
-	);
-	let request = gen_request(url, HttpMethod::Post).body(body.as_bytes());
+/// Sends a signed request. A token the API rejects despite its `exp`, such as
+/// one revoked by a login elsewhere, is replaced by a new login once. A
+/// rejected `token` setting is remembered so that it is skipped until the
+/// user enters another one.
+fn send(url: String, method: HttpMethod, body: Option<&[u8]>) -> Result<ValueRef, Error> {
+	let authorization = authorization()?;
+	let request = gen_request(url.clone(), method, &authorization);
+	let request = match body {
+		Some(body) => request.body(body),
+		None => request,
+	};
 
 	request.send();
 
-	if request.status_code() == 401 {
-		request.header("Authorization", &login()?).json()
+	let request = if request.status_code() == 401 {
+		if authorization == get_default("token") {
+			defaults_set("rejected_token", StringRef::from(authorization.as_str()).0);
+		}
+		let request = gen_request(url, method, &login(&authorization)?);
+		let request = match body {
+			Some(body) => request.body(body),
+			None => request,
+		};
+		request.send();
+		request
+	} else {
+		request
+	};
+	check_response(&request)?;
+
+	Ok(request.json()?)
+}
+
+fn get_default(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.unwrap_or_default()
+}
+
+/// Where page `page` of the knight listing starts: page `knight_page` of the
+/// uploads of `knights[knight]`.
+pub struct KnightCursor {
+	pub page: i32,
+	pub knights: Vec<String>,
+	pub knight: usize,
+	pub knight_page: i32,
+}
+
+/// The cursor kept by the last knight listing page, as
+/// `{page}|{knight}|{knight page}|{knight ids}`.
+pub fn knight_cursor() -> Option<KnightCursor> {
+	let value = get_default("knight_cursor");
+	let mut parts = value.split('|');
+	Some(KnightCursor {
+		page: parts.next()?.parse().ok()?,
+		knight: parts.next()?.parse().ok()?,
+		knight_page: parts.next()?.parse().ok()?,
+		knights: parts
+			.next()?
+			.split(',')
+			.filter(|id| !id.is_empty())
+			.map(String::from)
+			.collect(),
+	})
+}
+
+pub fn set_knight_cursor(cursor: &KnightCursor) {
+	let value = format!(
+		"{}|{}|{}|{}",
+		cursor.page,
+		cursor.knight,
+		cursor.knight_page,
+		cursor.knights.join(",")
+	);
+	defaults_set("knight_cursor", StringRef::from(value).0);
+}
+
+/// The `image-quality` tier the API picks page images from.
+fn image_quality() -> String {
+	let quality = get_default("image_quality");
+	if quality.is_empty() {
+		String::from("original")
 	} else {
-		request.json()
+		quality
 	}
 }
 
+/// The image host set in the settings, used instead of the `fileServer` of
+/// covers and pages.
+pub fn image_host() -> Option<String> {
+	let host = get_default("image_host");
+	let host = host.trim().trim_end_matches('/');
+	if host.is_empty() {
+		None
+	} else if host.starts_with("http") {
+		Some(String::from(host))
+	} else {
+		Some(format!("https://{}", host))
+	}
+}
+
+/// Runs an advanced search, limited to `category` unless it is empty.
+pub fn search(
+	keyword: String,
+	category: String,
+	sort: String,
+	page: i32,
+) -> Result<ValueRef, Error> {
+	let url = gen_search_url(sort.clone(), page);
+	let categories = if category.is_empty() {
+		json!([])
+	} else {
+		json!([category])
+	};
+	let body = json!({
+		"keyword": keyword,
+		"categories": categories,
+		"sort": sort,
+	})
+	.to_string();
+
+	send(url, HttpMethod::Post, Some(body.as_bytes()))
+}
+
 pub fn gen_login_url() -> String {
 	format!("{}/{}", API_URL, "auth/sign-in")
 }
@@ -138,6 +265,38 @@ pub fn gen_explore_url(category: String, sort: String, page: i32) -> String {
 	}
 }
 
+pub fn gen_tag_url(tag: String, sort: String, page: i32) -> String {
+	format!(
+		"{}/comics?page={}&t={}&s={}",
+		API_URL,
+		page,
+		encode_uri(tag),
+		sort,
+	)
+}
+
+pub fn gen_author_url(author: String, sort: String, page: i32) -> String {
+	format!(
+		"{}/comics?page={}&a={}&s={}",
+		API_URL,
+		page,
+		encode_uri(author),
+		sort,
+	)
+}
+
+pub fn gen_favourite_url(page: i32) -> String {
+	format!("{}/users/favourite?page={}&s=dd", API_URL, page)
+}
+
+pub fn gen_knight_rank_url() -> String {
+	format!("{}/comics/knight-leaderboard", API_URL)
+}
+
+pub fn gen_knight_comics_url(knight_id: String, page: i32) -> String {
+	format!("{}/comics?page={}&ca={}&s=dd", API_URL, page, knight_id)
+}
+
 pub fn gen_rank_url(time: String) -> String {
 	format!("{}/comics/leaderboard?tt={}&ct=VC", API_URL, time)
 }
@@ -146,8 +305,11 @@ pub fn gen_random_url() -> String {
 	format!("{}/comics/random", API_URL)
 }
 
-pub fn gen_search_url(page: i32) -> String {
-	format!("{}/comics/advanced-search?page={}&s=dd", API_URL, page)
+pub fn gen_search_url(sort: String, page: i32) -> String {
+	format!(
+		"{}/comics/advanced-search?page={}&s={}",
+		API_URL, page, sort
+	)
 }
 
 pub fn gen_manga_url(id: String) -> String {
@@ -176,14 +338,6 @@ pub fn gen_page_list_url(manga_id: String, chapter_id: String, page: i32) -> Str
 	)
 }
 
-pub fn get_json(url: String) -> Result<ValueRef, AidokuError> {
-	let request = gen_request(url, HttpMethod::Get);
-
-	request.send();
-
-	if request.status_code() == 401 {
-		request.header("Authorization", &login()?).json()
-	} else {
-		request.json()
-	}
+pub fn get_json(url: String) -> Result<ValueRef, Error> {
+	send(url, HttpMethod::Get, None)
 }
@@ -5,91 +5,41 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaPageResult, Page,
+};
+use zh_common::{
+	error::{self, page_list},
+	filter::FilterValues,
+	json::parse_list,
+	link::{deep_link, unsupported},
+	url::query_value,
 };
-use alloc::string::ToString;
 
 mod crypto;
 mod helper;
 mod parser;
 
-const FILTER_CATEGORY: [&str; 37] = [
-	"",
-	"嗶咔漢化",
-	"全彩",
-	"長篇",
-	"同人",
-	"短篇",
-	"圓神領域",
-	"碧藍幻想",
-	"CG雜圖",
-	"英語 ENG",
-	"生肉",
-	"純愛",
-	"百合花園",
-	"耽美花園",
-	"偽娘哲學",
-	"後宮閃光",
-	"扶他樂園",
-	"單行本",
-	"姐姐系",
-	"妹妹系",
-	"SM",
-	"性轉換",
-	"足の恋",
-	"人妻",
-	"NTR",
-	"強暴",
-	"非人類",
-	"艦隊收藏",
-	"Love Live",
-	"SAO 刀劍神域",
-	"Fate",
-	"東方",
-	"WEBTOON",
-	"禁書目錄",
-	"歐美",
-	"Cosplay",
-	"重口地帶",
-];
-const FILTER_SORT: [&str; 4] = ["dd", "da", "ld", "vd"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-	let mut sort = String::from("dd");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类别" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				sort = FILTER_SORT[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let author = filters.author();
+	let tag = filters.text("标签");
+	let category = filters.get("类别");
+	let sort = filters.get("排序");
 
-	let json = if query.is_empty() {
-		helper::get_json(helper::gen_explore_url(category, sort, page))?
+	// Tags are free text on the site, so they are a text filter rather than a
+	// select one.
+	let json = if !tag.is_empty() {
+		helper::get_json(helper::gen_tag_url(tag, sort, page))?
+	} else if !query.is_empty() {
+		helper::search(query, category, sort, page)?
+	} else if !author.is_empty() {
+		helper::get_json(helper::gen_author_url(author, sort, page))?
 	} else {
-		helper::search(query, page)?
+		helper::get_json(helper::gen_explore_url(category, sort, page))?
 	};
 
 	let data = json.as_object()?;
@@ -98,8 +48,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let list = data.get("docs").as_array()?;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
-		has_more: parser::has_more(data),
+		manga: parse_list(list, parser::parse_manga),
+		has_more: parser::has_more(data)?,
 	})
 }
 
@@ -107,6 +57,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let mut rank_time = String::new();
 	let mut is_random = false;
+	let mut is_favourite = false;
 	let mut category = String::new();
 	let sort = String::from("dd");
 
@@ -115,6 +66,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"周榜" => rank_time = String::from("D7"),
 		"月榜" => rank_time = String::from("D30"),
 		"随机本子" => is_random = true,
+		"骑士榜" => return get_knight_listing(page),
+		"我的收藏" => is_favourite = true,
 		"大湿推荐" => category = String::from("大濕推薦"),
 		"那年今天" => category = String::from("那年今天"),
 		"大家都在看" => category = String::from("大家都在看"),
@@ -126,6 +79,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		helper::gen_rank_url(rank_time.clone())
 	} else if is_random {
 		helper::gen_random_url()
+	} else if is_favourite {
+		helper::gen_favourite_url(page)
 	} else if !category.is_empty() {
 		helper::gen_explore_url(category, sort, page)
 	} else {
@@ -141,13 +96,13 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 
 	if !rank_time.is_empty() || is_random {
 		let list = data.get("comics").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parse_list(list, parser::parse_manga);
 		has_more = false;
 	} else {
 		let data = data.get("comics").as_object()?;
 		let list = data.get("docs").as_array()?;
-		mangas = parser::parse_manga_list(list);
-		has_more = parser::has_more(data);
+		mangas = parse_list(list, parser::parse_manga);
+		has_more = parser::has_more(data)?;
 	};
 
 	Ok(MangaPageResult {
@@ -156,6 +111,72 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	})
 }
 
+/// The knight leaderboard ranks uploaders rather than manga, so the listing
+/// goes through the uploads of each knight in rank order, newest first. The
+/// leaderboard is only loaded for the first page; each page then keeps where
+/// the next one starts.
+fn get_knight_listing(page: i32) -> Result<MangaPageResult> {
+	let cursor = if page == 1 {
+		let json = helper::get_json(helper::gen_knight_rank_url())?;
+		let data = json.as_object()?;
+		let data = data.get("data").as_object()?;
+		let knights = data
+			.get("users")
+			.as_array()?
+			.filter_map(|user| Some(user.as_object().ok()?.get("_id").as_string().ok()?.read()))
+			.collect::<Vec<String>>();
+		Some(helper::KnightCursor {
+			page,
+			knights,
+			knight: 0,
+			knight_page: 1,
+		})
+	} else {
+		helper::knight_cursor().filter(|cursor| cursor.page == page)
+	};
+	let Some(cursor) = cursor else {
+		return Ok(MangaPageResult {
+			manga: Vec::new(),
+			has_more: false,
+		});
+	};
+	let Some(knight) = cursor.knights.get(cursor.knight) else {
+		return Ok(MangaPageResult {
+			manga: Vec::new(),
+			has_more: false,
+		});
+	};
+
+	let url = helper::gen_knight_comics_url(knight.clone(), cursor.knight_page);
+	let json = helper::get_json(url)?;
+	let data = json.as_object()?;
+	let data = data.get("data").as_object()?;
+	let data = data.get("comics").as_object()?;
+	let list = data.get("docs").as_array()?;
+
+	let next = if parser::has_more(data)? {
+		helper::KnightCursor {
+			page: page + 1,
+			knight_page: cursor.knight_page + 1,
+			..cursor
+		}
+	} else {
+		helper::KnightCursor {
+			page: page + 1,
+			knight: cursor.knight + 1,
+			knight_page: 1,
+			..cursor
+		}
+	};
+	let has_more = next.knight < next.knights.len();
+	helper::set_knight_cursor(&next);
+
+	Ok(MangaPageResult {
+		manga: parse_list(list, parser::parse_manga),
+		has_more,
+	})
+}
+
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = helper::gen_manga_details_url(id);
@@ -163,43 +184,42 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data)
 }
 
+/// Lists the chapters from the first chunk of `eps` alone. The API numbers
+/// the chapters of a manga 1 to `total` by `order`, so the older chapters are
+/// filled in by number instead of being fetched 40 at a time, and only lack
+/// their titles.
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let mut page = 1;
-	let url = helper::gen_chapter_list_url(id.clone(), page);
+	let url = helper::gen_chapter_list_url(id.clone(), 1);
 	let json = helper::get_json(url)?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let data = data.get("eps").as_object()?;
 	let list = data.get("docs").as_array()?;
-	let pages = data.get("pages").as_int()? as i32;
+	let total = data.get("total").as_int()?;
 	let mut chapters = parser::parse_chapter_list(id.clone(), list);
 
-	while page < pages {
-		page += 1;
-		let next_page_chapters = get_chapter_list_by_page(id.clone(), page);
-		chapters = [chapters, next_page_chapters].concat();
+	let oldest = chapters
+		.iter()
+		.map(|chapter| chapter.chapter as i64)
+		.min()
+		.unwrap_or(total + 1);
+	for order in (1..oldest).rev() {
+		chapters.push(parser::gen_chapter(id.clone(), order, String::new()));
 	}
 
 	Ok(chapters)
 }
 
-fn get_chapter_list_by_page(id: String, page: i32) -> Vec<Chapter> {
-	let url = helper::gen_chapter_list_url(id.clone(), page);
-	let json = helper::get_json(url).unwrap();
-	let data = json.as_object().unwrap();
-	let data = data.get("data").as_object().unwrap();
-	let data = data.get("eps").as_object().unwrap();
-	let list = data.get("docs").as_array().unwrap();
-
-	parser::parse_chapter_list(id, list)
-}
-
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
+	page_list(fetch_page_list(manga_id, chapter_id))
+}
+
+fn fetch_page_list(manga_id: String, chapter_id: String) -> error::Result<Vec<Page>> {
 	let mut page = 1;
 	let url = helper::gen_page_list_url(manga_id.clone(), chapter_id.clone(), page);
 	let json = helper::get_json(url)?;
@@ -212,21 +232,34 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	while page < pages {
 		page += 1;
-		let next_page_page_list = get_page_list_by_page(manga_id.clone(), chapter_id.clone(), page);
+		let next_page_page_list =
+			get_page_list_by_page(manga_id.clone(), chapter_id.clone(), page)?;
 		page_list = [page_list, next_page_page_list].concat();
 	}
 
 	Ok(page_list)
 }
 
-fn get_page_list_by_page(manga_id: String, chapter_id: String, page: i32) -> Vec<Page> {
+fn get_page_list_by_page(
+	manga_id: String,
+	chapter_id: String,
+	page: i32,
+) -> error::Result<Vec<Page>> {
 	let url = helper::gen_page_list_url(manga_id.clone(), chapter_id.clone(), page);
-	let json = helper::get_json(url).unwrap();
-	let data = json.as_object().unwrap();
-	let data = data.get("data").as_object().unwrap();
-	let data = data.get("pages").as_object().unwrap();
-	let list = data.get("docs").as_array().unwrap();
-	let limit = data.get("limit").as_int().unwrap() as i32;
-
-	parser::parse_page_list(list, (page - 1) * limit)
+	let json = helper::get_json(url)?;
+	let data = json.as_object()?;
+	let data = data.get("data").as_object()?;
+	let data = data.get("pages").as_object()?;
+	let list = data.get("docs").as_array()?;
+	let limit = data.get("limit").as_int()? as i32;
+
+	Ok(parser::parse_page_list(list, (page - 1) * limit))
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let manga_id = query_value(&url, "cid").ok_or_else(unsupported)?;
+	let chapter_id = query_value(&url, "chapter");
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
 }
@@ -1,39 +1,31 @@
 use aidoku::{
+	error::Result,
 	prelude::*,
-	std::{ArrayRef, ObjectRef, String, Vec},
+	std::{ArrayRef, ObjectRef, String, ValueRef, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
 
 use crate::helper;
 
-pub fn has_more(data: ObjectRef) -> bool {
-	let page = data.get("page").as_int().unwrap();
-	let pages = data.get("pages").as_int().unwrap();
-	pages > page
+pub fn has_more(data: ObjectRef) -> Result<bool> {
+	let page = data.get("page").as_int()?;
+	let pages = data.get("pages").as_int()?;
+	Ok(pages > page)
 }
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
-	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
-}
-
-pub fn parse_manga(manga: ObjectRef) -> Manga {
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
 	let manga = match manga.get("comic").as_object() {
 		Ok(value) => value,
 		Err(_) => manga,
 	};
-	let id = manga.get("_id").as_string().unwrap().read();
-	let thumb = manga.get("thumb").as_object().unwrap();
-	let host = thumb.get("fileServer").as_string().unwrap().read();
-	let path = thumb.get("path").as_string().unwrap().read();
-	let cover = format!("{}/static/{}", host, path);
-	let title = manga.get("title").as_string().unwrap().read();
+	let id = manga.get("_id").as_string()?.read();
+	let cover = media_url(manga.get("thumb")).unwrap_or_default();
+	let title = manga.get("title").as_string()?.read();
 	let author = manga
 		.get("author")
 		.as_string()
-		.unwrap()
+		.unwrap_or_default()
 		.read()
 		.split("&")
 		.map(|a| a.trim().to_string())
@@ -46,11 +38,14 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		.unwrap_or_default()
 		.read();
 	let url = helper::gen_manga_url(id.clone());
+	// The details also carry the uploader's tags, which can be browsed with a
+	// `#tag` search.
 	let categories = manga
 		.get("categories")
 		.as_array()
-		.unwrap()
-		.map(|category| category.as_string().unwrap().read())
+		.unwrap_or_default()
+		.chain(manga.get("tags").as_array().unwrap_or_default())
+		.filter_map(|category| Some(category.as_string().ok()?.read()))
 		.collect::<Vec<String>>();
 	let status = if manga.get("finished").as_bool().unwrap_or_default() {
 		MangaStatus::Completed
@@ -63,7 +58,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	} else {
 		MangaViewer::Rtl
 	};
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -75,43 +70,63 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
+}
+
+/// Builds the URL of a `{ fileServer, path }` media object, on the image host
+/// from the settings when one is set.
+fn media_url(media: ValueRef) -> Result<String> {
+	let media = media.as_object()?;
+	let path = media.get("path").as_string()?.read();
+	let host = match helper::image_host() {
+		Some(host) => host,
+		None => media.get("fileServer").as_string()?.read(),
+	};
+	Ok(format!("{}/static/{}", host, path))
 }
 
 pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Vec<Chapter> {
 	let mut chapters: Vec<Chapter> = Vec::new();
 
 	for item in chapter_list {
-		let item = item.as_object().unwrap();
-		let order = item.get("order").as_int().unwrap();
-		let id = order.to_string();
-		let title = item.get("title").as_string().unwrap().read();
-		let chapter = order as f32;
-		let url = helper::gen_chapter_url(manga_id.clone(), id.clone());
-		chapters.push(Chapter {
-			id,
-			title,
-			chapter,
-			url,
-			..Default::default()
-		});
+		let Ok(item) = item.as_object() else {
+			continue;
+		};
+		let Ok(order) = item.get("order").as_int() else {
+			continue;
+		};
+		let title = item.get("title").as_string().unwrap_or_default().read();
+		chapters.push(gen_chapter(manga_id.clone(), order, title));
 	}
 
 	chapters
 }
 
+/// The chapter with the given `order`, which is also its id and number.
+pub fn gen_chapter(manga_id: String, order: i64, title: String) -> Chapter {
+	let id = order.to_string();
+	let url = helper::gen_chapter_url(manga_id, id.clone());
+	Chapter {
+		id,
+		title,
+		chapter: order as f32,
+		url,
+		..Default::default()
+	}
+}
+
 pub fn parse_page_list(page_list: ArrayRef, offset: i32) -> Vec<Page> {
 	let mut pages: Vec<Page> = Vec::new();
 
-	for (index, item) in page_list.enumerate() {
-		let item = item.as_object().unwrap();
-		let index = index as i32 + offset;
-		let media = item.get("media").as_object().unwrap();
-		let host = media.get("fileServer").as_string().unwrap().read();
-		let path = media.get("path").as_string().unwrap().read();
-		let url = format!("{}/static/{}", host, path);
+	for item in page_list {
+		let Ok(url) = item
+			.as_object()
+			.and_then(|item| media_url(item.get("media")))
+		else {
+			continue;
+		};
 		pages.push(Page {
-			index,
+			index: offset + pages.len() as i32,
 			url,
 			..Default::default()
 		});
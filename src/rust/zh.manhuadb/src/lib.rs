@@ -10,25 +10,22 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
 use base64::{engine::general_purpose, Engine};
+use zh_common::{
+	filter::FilterValues,
+	id::html_slug,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://www.manhuadb.com";
 const STATIC_URL: &str = "https://i2.manhuadb.com/static";
 
-const FILTER_REGION: [&str; 7] = ["", "4", "5", "6", "7", "8", "9"];
-const FILTER_AUDIENCE: [&str; 10] = ["", "3", "4", "5", "6", "7", "9", "10", "11", "12"];
-const FILTER_STATUS: [&str; 3] = ["", "1", "2"];
-const FILTER_CATEGORY: [&str; 66] = [
-	"", "26", "66", "12", "64", "39", "41", "20", "40", "33", "48", "13", "46", "44", "71", "52",
-	"43", "27", "18", "55", "72", "32", "59", "16", "53", "56", "80", "54", "60", "73", "47", "58",
-	"30", "51", "21", "22", "9", "11", "45", "68", "67", "19", "70", "57", "29", "61", "78", "37",
-	"76", "17", "23", "65", "28", "10", "49", "69", "62", "50", "42", "34", "77", "74", "63", "81",
-	"82", "83",
-];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 fn handle_cover(mut cover: String) -> String {
 	if !cover.starts_with("https") {
@@ -39,38 +36,12 @@ fn handle_cover(mut cover: String) -> String {
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut region = String::new();
-	let mut audience = String::new();
-	let mut status = String::new();
-	let mut category = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"原作地区" => {
-						region = FILTER_REGION[index].to_string();
-					}
-					"面向读者" => {
-						audience = FILTER_AUDIENCE[index].to_string();
-					}
-					"连载状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					"类型" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let region = filters.get("原作地区");
+	let audience = filters.get("面向读者");
+	let status = filters.get("连载状态");
+	let category = filters.get("类型");
 
 	let url = if query.is_empty() {
 		format!(
@@ -242,3 +213,22 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[kind, id] if kind == "manhua" => (id.clone(), None),
+		// Later pages of a chapter end with `_p<page>.html`.
+		[kind, id, chapter] if kind == "manhua" => {
+			let chapter_id = html_slug(chapter);
+			let chapter_id = match chapter_id.split_once("_p") {
+				Some((chapter_id, _)) => chapter_id.to_string(),
+				None => chapter_id,
+			};
+			(id.clone(), Some(chapter_id))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
@@ -10,6 +10,7 @@ use aidoku::{
 };
 use alloc::string::ToString;
 use md5::compute;
+use zh_common::link::unsupported;
 
 const WWW_URL: &str = "https://www.manhuaren.com";
 const API_URL: &str = "https://mangaapi.manhuaren.com";
@@ -28,6 +29,29 @@ pub fn get_json(url: String) -> Result<ValueRef, AidokuError> {
 		.json()
 }
 
+/// Reads the numeric manga ID from a web page, which declares it in a script
+/// as `COMIC_MID=123` on manga pages or `DM5_MID=123` on chapter pages.
+pub fn get_manga_id(url: String) -> Result<String, AidokuError> {
+	let text = Request::new(url, HttpMethod::Get).string()?;
+
+	for (index, _) in text.match_indices("MID") {
+		let rest = text[index + 3..].trim_start();
+		let rest = match rest.strip_prefix('=') {
+			Some(rest) => rest.trim_start(),
+			None => continue,
+		};
+		let id = rest
+			.chars()
+			.take_while(|c| c.is_ascii_digit())
+			.collect::<String>();
+		if !id.is_empty() {
+			return Ok(id);
+		}
+	}
+
+	Err(unsupported())
+}
+
 pub fn gen_gsn_hash(mut params: Vec<(String, String)>) -> String {
 	let mut hash = String::new();
 
@@ -46,7 +70,10 @@ pub fn gen_gsn_hash(mut params: Vec<(String, String)>) -> String {
 }
 
 pub fn gen_query_string(mut params: Vec<(String, String)>) -> String {
-	let uid = defaults_get("uid").unwrap().as_string().unwrap().read();
+	let uid = defaults_get("uid")
+		.and_then(|uid| uid.as_string())
+		.map(|uid| uid.read())
+		.unwrap_or_default();
 
 	params.push((String::from("gak"), String::from("ios_manhuaren2")));
 	params.push((String::from("gft"), String::from("json")));
@@ -5,55 +5,28 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{net::Request, String, Vec},
-	Chapter, Filter, FilterType, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	json::parse_list,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 mod helper;
 mod parser;
 
-const FILTER_CATEGORY: [&str; 30] = [
-	"0", "31", "26", "1", "3", "27", "5", "2", "6", "8", "9", "25", "10", "11", "12", "17", "33",
-	"37", "14", "15", "29", "20", "21", "4", "7", "30", "34", "36", "40", "61",
-];
-const FILTER_STATUS: [&str; 3] = ["0", "1", "2"];
-const FILTER_SORT: [&str; 3] = ["0", "1", "2"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::from("0");
-	let mut status = String::from("0");
-	let mut sort = String::from("0");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类别" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					"状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				sort = FILTER_SORT[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let category = filters.get("类别");
+	let status = filters.get("状态");
+	let sort = filters.get("排序");
 
 	let url = if query.is_empty() {
 		helper::gen_explore_url(category, status, sort, page)
@@ -69,12 +42,12 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 
 	if query.is_empty() {
 		let list = data.get("mangas").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parse_list(list, parser::parse_manga);
 		has_more = true;
 	} else {
 		let list = data.get("result").as_array()?;
 		let total = data.get("total").as_int()? as i32;
-		mangas = parser::parse_manga_list(list);
+		mangas = parse_list(list, parser::parse_manga);
 		has_more = page * 20 < total;
 	}
 
@@ -91,7 +64,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let data = json.as_object()?;
 	let data = data.get("response").as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data)
 }
 
 #[get_chapter_list]
@@ -111,7 +84,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let data = json.as_object()?;
 	let data = data.get("response").as_object()?;
 
-	Ok(parser::parse_page_list(data))
+	parser::parse_page_list(data)
 }
 
 #[modify_image_request]
@@ -122,3 +95,24 @@ fn modify_image_request(request: Request) {
 		.header("Referer", "http://www.dm5.com/dm5api/")
 		.header("ClubReferer", "http://mangaapi.manhuaren.com/");
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = path_segments(&url);
+	let slug = match segments.as_slice() {
+		[slug] => slug,
+		_ => return Err(unsupported()),
+	};
+	// Chapter pages are `/m<chapter id>/` and manga pages `/manhua-<slug>/`.
+	let chapter_id = slug
+		.strip_prefix('m')
+		.filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+		.map(|id| id.to_string());
+	if chapter_id.is_none() && !slug.starts_with("manhua-") {
+		return Err(unsupported());
+	}
+	// Neither carries the numeric manga ID the API expects.
+	let manga_id = helper::get_manga_id(url.clone())?;
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
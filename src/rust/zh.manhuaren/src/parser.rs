@@ -1,4 +1,5 @@
 use aidoku::{
+	error::Result,
 	prelude::*,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
@@ -7,25 +8,19 @@ use alloc::string::ToString;
 
 use crate::helper;
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
-	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
-}
-
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let id = manga.get("mangaId").as_int().unwrap().to_string();
-	let cover = manga.get("mangaCoverimageUrl").as_string().unwrap();
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let id = manga.get("mangaId").as_int()?.to_string();
 	let cover = manga
 		.get("mangaPicimageUrl")
 		.as_string()
-		.unwrap_or(cover)
-		.read();
-	let title = manga.get("mangaName").as_string().unwrap().read();
+		.or_else(|_| manga.get("mangaCoverimageUrl").as_string())
+		.map(|cover| cover.read())
+		.unwrap_or_default();
+	let title = manga.get("mangaName").as_string()?.read();
 	let author = manga
 		.get("mangaAuthor")
 		.as_string()
-		.unwrap()
+		.unwrap_or_default()
 		.read()
 		.trim()
 		.to_string();
@@ -39,7 +34,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	let categories = manga
 		.get("mangaTheme")
 		.as_string()
-		.unwrap()
+		.unwrap_or_default()
 		.read()
 		.split(" ")
 		.map(|category| category.to_string())
@@ -52,7 +47,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	};
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -64,13 +59,13 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
 pub fn parse_chapter_list(manga: ObjectRef) -> Vec<Chapter> {
-	let words = manga.get("mangaWords").as_array().unwrap();
-	let rolls = manga.get("mangaRolls").as_array().unwrap();
-	let episode = manga.get("mangaEpisode").as_array().unwrap();
+	let words = manga.get("mangaWords").as_array().unwrap_or_default();
+	let rolls = manga.get("mangaRolls").as_array().unwrap_or_default();
+	let episode = manga.get("mangaEpisode").as_array().unwrap_or_default();
 	let mut chapters: Vec<Chapter> = Vec::new();
 	chapters.append(&mut parse_chapters(words));
 	chapters.append(&mut parse_chapters(rolls));
@@ -84,10 +79,23 @@ pub fn parse_chapters(chapter_list: ArrayRef) -> Vec<Chapter> {
 	let mut chapters: Vec<Chapter> = Vec::new();
 
 	for item in chapter_list {
-		let item = item.as_object().unwrap();
-		let id = item.get("sectionId").as_int().unwrap().to_string();
-		let section_title = item.get("sectionTitle").as_string().unwrap().read();
-		let section_name = item.get("sectionName").as_string().unwrap().read();
+		let Ok(item) = item.as_object() else {
+			continue;
+		};
+		let Ok(id) = item.get("sectionId").as_int() else {
+			continue;
+		};
+		let id = id.to_string();
+		let section_title = item
+			.get("sectionTitle")
+			.as_string()
+			.unwrap_or_default()
+			.read();
+		let section_name = item
+			.get("sectionName")
+			.as_string()
+			.unwrap_or_default()
+			.read();
 		let is_must_pay = item.get("isMustPay").as_int().unwrap_or_default();
 		let title = if section_title.is_empty() {
 			section_name
@@ -110,28 +118,29 @@ pub fn parse_chapters(chapter_list: ArrayRef) -> Vec<Chapter> {
 	chapters
 }
 
-pub fn parse_page_list(chapter: ObjectRef) -> Vec<Page> {
-	let list = chapter.get("mangaSectionImages").as_array().unwrap();
-	let host_list = chapter.get("hostList").as_array().unwrap();
-	let query = chapter.get("query").as_string().unwrap();
+pub fn parse_page_list(chapter: ObjectRef) -> Result<Vec<Page>> {
+	let list = chapter.get("mangaSectionImages").as_array()?;
+	let host_list = chapter.get("hostList").as_array()?;
+	let query = chapter.get("query").as_string().unwrap_or_default();
 	let mut pages: Vec<Page> = Vec::new();
 
 	if host_list.is_empty() {
-		return pages;
+		return Ok(pages);
 	}
 
-	let host = host_list.get(0).as_string().unwrap().read();
+	let host = host_list.get(0).as_string()?.read();
 
-	for (index, item) in list.enumerate() {
-		let item = item.as_string().unwrap().read();
-		let index = index as i32;
-		let url = format!("{}{}{}", host, item, query);
+	for item in list {
+		let Ok(item) = item.as_string() else {
+			continue;
+		};
+		let url = format!("{}{}{}", host, item.read(), query);
 		pages.push(Page {
-			index,
+			index: pages.len() as i32,
 			url,
 			..Default::default()
 		});
 	}
 
-	pages
+	Ok(pages)
 }
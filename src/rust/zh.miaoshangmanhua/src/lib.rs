@@ -13,6 +13,11 @@ use aidoku::{
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::{select_value, sort_value},
+	id::html_slug,
+	url::unproxy,
+};
 
 const WWW_URL: &str = "https://www.miaoshangmanhua.cc";
 
@@ -25,19 +30,6 @@ const FILTER_TAG: [&str; 80] = [
 ];
 const FILTER_ORDER: [&str; 2] = ["hits", "addtime"];
 
-fn handle_cover_url(url: String) -> String {
-	if url.contains("url=") {
-		url.split("url=")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace("&w=320&q=60", "")
-	} else {
-		url
-	}
-}
-
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let mut query = String::new();
@@ -53,18 +45,17 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
 					"标签" => {
-						tag = FILTER_TAG[index].to_string();
+						tag = select_value(&FILTER_TAG, index);
 					}
 					_ => continue,
 				}
 			}
 			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
+				let (index, _) = match sort_value(&filter) {
+					Some(value) => value,
+					None => continue,
 				};
-				let index = value.get("index").as_int()? as usize;
-				order = FILTER_ORDER[index].to_string();
+				order = select_value(&FILTER_ORDER, index);
 			}
 			_ => continue,
 		}
@@ -92,17 +83,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("li:nth-child(1)>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
-		let cover = handle_cover_url(item.select("li:nth-child(1)>a>img").attr("src").read());
+		let id = html_slug(item.select("li:nth-child(1)>a").attr("href").read());
+		let cover = unproxy(item.select("li:nth-child(1)>a>img").attr("src").read());
 		let title = item.select(".title>a").text().read();
 		mangas.push(Manga {
 			id,
@@ -151,17 +133,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".pic>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
-		let cover = handle_cover_url(item.select(".pic>a>img").attr("src").read());
+		let id = html_slug(item.select(".pic>a").attr("href").read());
+		let cover = unproxy(item.select(".pic>a>img").attr("src").read());
 		let title = item.select(".title>a").text().read();
 		mangas.push(Manga {
 			id,
@@ -181,7 +154,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/{}.html", WWW_URL, id.clone());
 	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
-	let cover = handle_cover_url(html.select(".cy_info_cover>a>img").attr("src").read());
+	let cover = unproxy(html.select(".cy_info_cover>a>img").attr("src").read());
 	let title = html.select(".cy_title>h1").text().read();
 	let author = html
 		.select(".cy_intro_l>.cy_xinxi:nth-child(4)>span:nth-child(1)>a")
@@ -239,15 +212,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = html_slug(item.attr("href").read());
 		let title = item.select("p").text().read().trim().to_string();
 		let chapter = (len - index) as f32;
 		let url = format!(
@@ -10,71 +10,32 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::html_slug,
+	link::{deep_link, unsupported},
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://www.mkzhan.com";
 const API_URL: &str = "https://comic.mkzcdn.com";
 
-const FILTER_THEME: [&str; 24] = [
-	"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "12", "13", "14", "15", "16", "17",
-	"18", "19", "20", "21", "23", "24", "26",
-];
-const FILTER_FINISH: [&str; 3] = ["", "1", "2"];
-const FILTER_AUDIENCE: [&str; 5] = ["", "1", "2", "3", "4"];
-const FILTER_COPYRIGHT: [&str; 3] = ["", "1", "2"];
-const FILTER_FREE: [&str; 4] = ["", "is_free=1", "is_fee=1", "is_vip=1"];
-const FILTER_ORDER: [&str; 3] = ["3", "1", "2"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut theme_id = String::from("0");
-	let mut finish = String::new();
-	let mut audience = String::new();
-	let mut copyright = String::new();
-	let mut free = String::new();
-	let mut order = String::from("3");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"题材" => {
-						theme_id = FILTER_THEME[index].to_string();
-					}
-					"进度" => {
-						finish = FILTER_FINISH[index].to_string();
-					}
-					"受众" => {
-						audience = FILTER_AUDIENCE[index].to_string();
-					}
-					"版权" => {
-						copyright = FILTER_COPYRIGHT[index].to_string();
-					}
-					"资费" => {
-						free = FILTER_FREE[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				order = FILTER_ORDER[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let theme_id = filters.get("题材");
+	let finish = filters.get("进度");
+	let audience = filters.get("受众");
+	let copyright = filters.get("版权");
+	let free = filters.get("资费");
+	let order = filters.get("排序");
 
 	let url = if query.is_empty() {
 		let mut base = format!(
@@ -304,3 +265,16 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[id] if id.chars().all(|c| c.is_ascii_digit()) => (id.clone(), None),
+		[id, chapter] if id.chars().all(|c| c.is_ascii_digit()) => {
+			(id.clone(), Some(html_slug(chapter)))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
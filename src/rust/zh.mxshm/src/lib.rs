@@ -5,63 +5,32 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		defaults::defaults_get,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{defaults::defaults_get, net::HttpMethod, String, Vec},
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	request::gen_request,
+	url::path_segments,
+};
 
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
-
-const FILTER_TAG: [&str; 23] = [
-	"全部", "青春", "性感", "长腿", "多人", "御姐", "巨乳", "新婚", "媳妇", "暧昧", "清纯", "调教",
-	"少妇", "风骚", "同居", "淫乱", "好友", "女神", "诱惑", "偷情", "出轨", "正妹", "家教",
-];
-const FILTER_AREA: [&str; 4] = ["-1", "1", "2", "3"];
-const FILTER_END: [&str; 3] = ["-1", "0", "1"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 fn get_url() -> String {
 	defaults_get("url").unwrap().as_string().unwrap().read()
 }
 
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
-}
-
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut tag = String::new();
-	let mut area = String::new();
-	let mut end = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"题材" => {
-						tag = FILTER_TAG[index].to_string();
-					}
-					"地区" => {
-						area = FILTER_AREA[index].to_string();
-					}
-					"进度" => {
-						end = FILTER_END[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let tag = filters.get("题材");
+	let area = filters.get("地区");
+	let end = filters.get("进度");
 
 	let url = if query.is_empty() {
 		format!(
@@ -84,15 +53,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select("a").attr("href").read());
 		let cover = item
 			.select("a>p")
 			.attr("style")
@@ -197,14 +158,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
 		let chapter = (index + 1) as f32;
 		let url = format!("{}/chapter/{}", get_url(), id.clone());
@@ -247,3 +201,24 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	match path_segments(&url).as_slice() {
+		[kind, id] if kind == "book" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		[kind, chapter_id] if kind == "chapter" => {
+			// Chapter pages only link back to their manga.
+			let url = format!("{}/chapter/{}", get_url(), chapter_id);
+			let html = gen_request(url, HttpMethod::Get).html()?;
+			let manga_id = last_segment(html.select("a[href*='/book/']").attr("href").read());
+			if manga_id.is_empty() {
+				return Err(unsupported());
+			}
+			Ok(deep_link(
+				get_manga_details(manga_id)?,
+				Some(chapter_id.clone()),
+			))
+		}
+		_ => Err(unsupported()),
+	}
+}
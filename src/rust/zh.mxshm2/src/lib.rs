@@ -10,67 +10,36 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	request::user_agent,
+	url::path_segments,
+};
 
-const FILTER_TAG: [&str; 23] = [
-	"全部", "青春", "性感", "长腿", "多人", "御姐", "巨乳", "新婚", "媳妇", "暧昧", "清纯", "调教",
-	"少妇", "风骚", "同居", "淫乱", "好友", "女神", "诱惑", "偷情", "出轨", "正妹", "家教",
-];
-const FILTER_AREA: [&str; 4] = ["-1", "1", "2", "3"];
-const FILTER_END: [&str; 3] = ["-1", "0", "1"];
-const FILTER_MANGA_LIST: [&str; 2] = ["booklist", "update"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 fn get_url() -> String {
 	defaults_get("url").unwrap().as_string().unwrap().read()
 }
 
 fn gen_request(url: String, method: HttpMethod) -> Request {
-	let user_agent = user_agent();
-
-	Request::new(url, method).header("User-Agent", &user_agent)
-}
-
-fn user_agent() -> String {
-	defaults_get("User-Agent").unwrap().as_string().unwrap().read()
+	Request::new(url, method).header("User-Agent", &user_agent())
 }
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut tag = String::new();
-	let mut area = String::new();
-	let mut end = String::new();
-	let mut manga_list = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"题材" => {
-						tag = FILTER_TAG[index].to_string();
-					}
-					"地区" => {
-						area = FILTER_AREA[index].to_string();
-					}
-					"进度" => {
-						end = FILTER_END[index].to_string();
-					}
-					"列表" => {
-						manga_list = FILTER_MANGA_LIST[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let tag = filters.get("题材");
+	let area = filters.get("地区");
+	let end = filters.get("进度");
+	let manga_list = filters.get("列表");
 
 	let url = if query.is_empty() {
 		format!(
@@ -94,15 +63,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select("a").attr("href").read());
 		let cover = item
 			.select("a>p")
 			.attr("style")
@@ -207,14 +168,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
 		let chapter = (index + 1) as f32;
 		let url = format!("{}/chapter/{}", get_url(), id.clone());
@@ -257,3 +211,24 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	match path_segments(&url).as_slice() {
+		[kind, id] if kind == "book" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		[kind, chapter_id] if kind == "chapter" => {
+			// Chapter pages only link back to their manga.
+			let url = format!("{}/chapter/{}", get_url(), chapter_id);
+			let html = gen_request(url, HttpMethod::Get).html()?;
+			let manga_id = last_segment(html.select("a[href*='/book/']").attr("href").read());
+			if manga_id.is_empty() {
+				return Err(unsupported());
+			}
+			Ok(deep_link(
+				get_manga_details(manga_id)?,
+				Some(chapter_id.clone()),
+			))
+		}
+		_ => Err(unsupported()),
+	}
+}
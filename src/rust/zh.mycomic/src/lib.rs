@@ -10,130 +10,36 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
-use regex::Regex;
+use zh_common::{
+	chapter::extract_chapter_number,
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	url::{absolute, path_segments},
+};
 
 const WWW_URL: &str = "https://mycomic.com/cn";
 
-const FILTER_TAG: [&str; 39] = [
-	"",
-	"mohuan",
-	"mofa",
-	"rexue",
-	"maoxian",
-	"xuanyi",
-	"zhentan",
-	"aiqing",
-	"xiaoyuan",
-	"gaoxiao",
-	"sige",
-	"kehuan",
-	"shengui",
-	"wudao",
-	"yinyue",
-	"baihe",
-	"hougong",
-	"jizhan",
-	"gedou",
-	"kongbu",
-	"mengxi",
-	"wuxia",
-	"shehui",
-	"lishi",
-	"danmei",
-	"lizhi",
-	"zhichang",
-	"shenghuo",
-	"zhiyu",
-	"weiniang",
-	"heidao",
-	"zhanzheng",
-	"jingji",
-	"tiyu",
-	"meishi",
-	"funv",
-	"zhainan",
-	"tuili",
-	"zazhi",
-];
-const FILTER_COUNTRY: [&str; 7] = ["", "japan", "hongkong", "europe", "china", "korea", "other"];
-const FILTER_AUDIENCE: [&str; 6] = ["", "shaonv", "shaonian", "qingnian", "ertong", "tongyong"];
-const FILTER_YEAR: [&str; 21] = [
-	"", "2025", "2024", "2023", "2022", "2021", "2020", "2019", "2018", "2017", "2016", "2015",
-	"2014", "2013", "2012", "2011", "2010", "200x", "199x", "198x", "197x",
-];
-const FILTER_END: [&str; 3] = ["", "0", "1"];
-const FILTER_SORT: [&str; 3] = ["", "update", "views"];
-
-fn extract_chapter_number(title: &str) -> Option<f32> {
-	let re = Regex::new(r"(?:第\s*)(\d+(?:\.\d+)?)|(\d+(?:\.\d+)?)\s*(?:话|話|章|回|卷|册|冊)").unwrap();
-	if let Some(captures) = re.captures(title) {
-		let num_match = captures.get(1).or_else(|| captures.get(2));
-		if let Some(num_match) = num_match {
-			if let Ok(num) = num_match.as_str().parse::<f32>() {
-				return Some(num);
-			}
-		}
-	}
-	None
-}
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut tag = String::new();
-	let mut country = String::new();
-	let mut audience = String::new();
-	let mut year = String::new();
-	let mut end = String::new();
-	let mut sort = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类型" => {
-						tag = FILTER_TAG[index].to_string();
-					}
-					"地区" => {
-						country = FILTER_COUNTRY[index].to_string();
-					}
-					"受众" => {
-						audience = FILTER_AUDIENCE[index].to_string();
-					}
-					"年份" => {
-						year = FILTER_YEAR[index].to_string();
-					}
-					"进度" => {
-						end = FILTER_END[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				let ascending = value.get("ascending").as_bool()?;
-				sort = FILTER_SORT[index].to_string();
-
-				if sort.is_empty() && ascending {
-					sort.push_str("time");
-				} else if !sort.is_empty() && !ascending {
-					sort = format!("-{}", sort)
-				}
-			}
-			_ => continue,
-		}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let tag = filters.get("类型");
+	let country = filters.get("地区");
+	let audience = filters.get("受众");
+	let year = filters.get("年份");
+	let end = filters.get("进度");
+	let mut sort = filters.get("排序");
+	if sort.is_empty() && filters.ascending() {
+		sort.push_str("time");
+	} else if !sort.is_empty() && !filters.ascending() {
+		sort = format!("-{}", sort)
 	}
 
 	let url = if query.is_empty() {
@@ -165,16 +71,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select("a").attr("href").read());
 		let img = item.select("a>img");
 		let mut cover = img.attr("data-src").read();
 		if cover.is_empty() {
@@ -215,11 +112,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		cover = html.select("img.object-cover").attr("src").read();
 	}
 	if !cover.is_empty() && !cover.starts_with("http") {
-		if cover.starts_with("//") {
-			cover = format!("https:{}", cover);
-		} else if cover.starts_with("/") {
-			cover = format!("{}{}", cdn_base, cover);
-		}
+		cover = absolute(&cdn_base, &cover);
 	}
 	let title = html
 		.select("title")
@@ -373,3 +266,24 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	match path_segments(&url).as_slice() {
+		[.., kind, id] if kind == "comics" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		[.., kind, chapter_id] if kind == "chapters" => {
+			// Chapter pages only link back to their manga.
+			let url = format!("{}/chapters/{}", WWW_URL, chapter_id);
+			let html = Request::new(url, HttpMethod::Get).html()?;
+			let manga_id = last_segment(html.select("a[href*='/comics/']").attr("href").read());
+			if manga_id.is_empty() {
+				return Err(unsupported());
+			}
+			Ok(deep_link(
+				get_manga_details(manga_id)?,
+				Some(chapter_id.clone()),
+			))
+		}
+		_ => Err(unsupported()),
+	}
+}
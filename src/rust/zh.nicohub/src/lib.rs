@@ -11,7 +11,7 @@ use aidoku::{
 	},
 	Chapter, Filter, FilterType, Manga, MangaPageResult, Page,
 };
-use alloc::string::ToString;
+use zh_common::filter::select_value;
 
 mod parser;
 
@@ -96,16 +96,16 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
 					"题材" => {
-						category = FILTER_CATEGORY[index].to_string();
+						category = select_value(&FILTER_CATEGORY, index);
 					}
 					"地区" => {
-						region = FILTER_REGION[index].to_string();
+						region = select_value(&FILTER_REGION, index);
 					}
 					"受众" => {
-						audience = FILTER_AUDIENCE[index].to_string();
+						audience = select_value(&FILTER_AUDIENCE, index);
 					}
 					"连载" => {
-						status = FILTER_STATUS[index].to_string();
+						status = select_value(&FILTER_STATUS, index);
 					}
 					_ => continue,
 				}
@@ -1,5 +1,4 @@
 use aidoku::{
-	error::{AidokuError, AidokuErrorKind},
 	helpers::substring::Substring,
 	std::{
 		defaults::{defaults_get, defaults_set},
@@ -8,11 +7,12 @@ use aidoku::{
 	},
 };
 use alloc::{format, string::ToString};
+use zh_common::error::{check_response, Error, SourceError};
 
 pub const WWW_URL: &str = "https://noy1.top";
 const PIC_URL: &str = "https://img.noy.asia";
 
-pub fn explore(tag: String, sort: String, page: i32) -> Result<ValueRef, AidokuError> {
+pub fn explore(tag: String, sort: String, page: i32) -> Result<ValueRef, Error> {
 	let url = if tag.is_empty() {
 		format!("{}/api/booklist_v2", WWW_URL)
 	} else {
@@ -27,14 +27,14 @@ pub fn explore(tag: String, sort: String, page: i32) -> Result<ValueRef, AidokuE
 	get_json(url, body)
 }
 
-pub fn search(keyword: String, page: i32) -> Result<ValueRef, AidokuError> {
+pub fn search(keyword: String, page: i32) -> Result<ValueRef, Error> {
 	let url = format!("{}/api/search_v2", WWW_URL);
 	let body = format!("info={}&type=de&sort=bid&page={}", keyword, page);
 
 	get_json(url, body)
 }
 
-pub fn rank(name: String, level: String, page: i32) -> Result<ValueRef, AidokuError> {
+pub fn rank(name: String, level: String, page: i32) -> Result<ValueRef, Error> {
 	let url = format!("{}/api/{}", WWW_URL, name);
 	let body = if !level.is_empty() {
 		format!("page={}&type={}", page, level)
@@ -45,80 +45,83 @@ pub fn rank(name: String, level: String, page: i32) -> Result<ValueRef, AidokuEr
 	get_json(url, body)
 }
 
-pub fn details(manga_id: String) -> Result<ValueRef, AidokuError> {
+pub fn details(manga_id: String) -> Result<ValueRef, Error> {
 	let url = format!("{}/api/getbookinfo", WWW_URL);
 	let body = format!("bid={}", manga_id);
 
 	get_json(url, body)
 }
 
-pub fn gen_request(url: String, method: HttpMethod) -> Request {
-	let session = defaults_get("session").unwrap().as_string().unwrap().read();
+pub fn gen_request(url: String, method: HttpMethod) -> Result<Request, Error> {
+	let session = get_default("session");
 	let session = if !url.contains("login") && session.is_empty() {
-		login().unwrap()
+		login()?
 	} else {
 		session
 	};
-	Request::new(url, method)
+	Ok(Request::new(url, method)
 		.header("Content-Type", "application/x-www-form-urlencoded")
-		.header("Cookie", &format!("NOY_SESSION={}", session))
+		.header("Cookie", &format!("NOY_SESSION={}", session)))
 }
 
-pub fn login() -> Result<String, AidokuError> {
+pub fn login() -> Result<String, Error> {
 	let url = format!("{}/api/login", WWW_URL);
-	let request = gen_request(url, HttpMethod::Post);
-	let username = defaults_get("username")?.as_string()?.read();
-	let password = defaults_get("password")?.as_string()?.read();
+	let request = gen_request(url, HttpMethod::Post)?;
+	let username = get_default("username");
+	let password = get_default("password");
 
 	if username.is_empty() || password.is_empty() {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(SourceError::Auth.into());
 	}
 
 	let body = format!("user={}&pass={}", username, password);
 	let request = request.body(body.as_bytes());
 
 	request.send();
+	check_response(&request)?;
 
 	if request.status_code() != 200 {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(SourceError::Auth.into());
 	}
 
-	let cookie_header = request.get_header("set-cookie").unwrap().read();
-	let session = cookie_header
+	let cookie_header = request
+		.get_header("set-cookie")
+		.map(|header| header.read())
+		.unwrap_or_default();
+	let Some(session) = cookie_header
 		.substring_after("NOY_SESSION=")
-		.unwrap()
-		.substring_before(";")
-		.unwrap();
+		.and_then(|cookie| cookie.substring_before(";"))
+	else {
+		// No session cookie means the credentials were rejected.
+		return Err(SourceError::Auth.into());
+	};
 
 	defaults_set("session", StringRef::from(session).0);
 
 	Ok(session.to_string())
 }
 
-pub fn get_json(url: String, body: String) -> Result<ValueRef, AidokuError> {
-	let mut request = gen_request(url.clone(), HttpMethod::Post).body(body.as_bytes());
+pub fn get_json(url: String, body: String) -> Result<ValueRef, Error> {
+	let mut request = gen_request(url.clone(), HttpMethod::Post)?.body(body.as_bytes());
 
 	request.send();
 
 	let status = request.status_code();
 
 	if status == 401 {
-		request
+		Ok(request
 			.header("Cookie", &format!("NOY_SESSION={}", &login()?))
-			.json()
+			.json()?)
 	} else {
+		check_response(&request)?;
 		let json = request.json()?;
 		let list = json.clone().as_array().unwrap_or_default();
 
 		if list.get(0).as_string().unwrap_or_default().read() == "login" {
-			request = gen_request(url, HttpMethod::Post)
+			request = gen_request(url, HttpMethod::Post)?
 				.body(body.as_bytes())
 				.header("Cookie", &format!("NOY_SESSION={}", &login()?));
-			return request.json();
+			return Ok(request.json()?);
 		} else {
 			return Ok(json);
 		}
@@ -140,3 +143,10 @@ pub fn gen_chapter_url(manga_id: String) -> String {
 pub fn gen_page_url(manga_id: String, page: i32) -> String {
 	format!("{}/{}/{}.webp", PIC_URL, manga_id, page)
 }
+
+fn get_default(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.unwrap_or_default()
+}
@@ -5,62 +5,27 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{net::Request, String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaPageResult, Page,
+};
+use zh_common::{
+	error::{self, page_list},
+	filter::FilterValues,
+	json::parse_list,
+	link::{deep_link, unsupported},
+	url::path_segments,
 };
-use alloc::string::ToString;
 
 mod helper;
 mod parser;
 
-const FILTER_TAG: [&str; 14] = [
-	"",
-	"蘿莉",
-	"全彩",
-	"長筒襪",
-	"原創",
-	"女學生制服",
-	"雙馬尾",
-	"巨乳",
-	"中出",
-	"性玩具",
-	"姐妹",
-	"百合",
-	"無修正",
-	"自慰",
-];
-const FILTER_SORT: [&str; 3] = ["bid", "views", "favorites"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut tag = String::new();
-	let mut sort = String::from("bid");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"标签" => {
-						tag = FILTER_TAG[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				sort = FILTER_SORT[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let tag = filters.get("标签");
+	let sort = filters.get("排序");
 
 	let json = if query.is_empty() {
 		helper::explore(tag.clone(), sort, page)?
@@ -79,7 +44,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let has_more = page * 20 < total;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
+		manga: parse_list(list, parser::parse_manga),
 		has_more,
 	})
 }
@@ -127,7 +92,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let has_more = page * 20 < total;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
+		manga: parse_list(list, parser::parse_manga),
 		has_more,
 	})
 }
@@ -137,7 +102,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let json = helper::details(id)?;
 	let data = json.as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data)
 }
 
 #[get_chapter_list]
@@ -159,6 +124,10 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 #[get_page_list]
 fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
+	page_list(fetch_page_list(manga_id))
+}
+
+fn fetch_page_list(manga_id: String) -> error::Result<Vec<Page>> {
 	let json = helper::details(manga_id.clone())?;
 	let data = json.as_object()?;
 	let len = data.get("Len").as_int()? as i32;
@@ -182,3 +151,20 @@ fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", helper::WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// The site routes with the fragment, e.g. `/#/book/123` and `/#/read/123`.
+	let route = match url.split_once('#') {
+		Some((_, route)) => route,
+		None => return Err(unsupported()),
+	};
+	match path_segments(route).as_slice() {
+		[kind, id] if kind == "book" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		// Every book is a single chapter whose ID is the book ID.
+		[kind, id, ..] if kind == "read" => {
+			Ok(deep_link(get_manga_details(id.clone())?, Some(id.clone())))
+		}
+		_ => Err(unsupported()),
+	}
+}
@@ -5,60 +5,30 @@ use aidoku::{
 	error::Result,
 	helpers::{substring::Substring, uri::encode_uri},
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{net::HttpMethod, String, Vec},
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	request::Profile,
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://se8.us/index.php";
-const UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1";
 
-const FILTER_TAG: [&str; 22] = [
-	"", "61", "63", "62", "64", "11", "15", "17", "29", "31", "67", "68", "69", "75", "78", "84",
-	"86", "87", "91", "98", "106", "114",
-];
-const FILTER_FINISH: [&str; 3] = ["", "1", "2"];
-const FILTER_ORDER: [&str; 2] = ["hits", "addtime"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut tag = String::new();
-	let mut finish = String::new();
-	let mut order = String::from("hits");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"标签" => {
-						tag = FILTER_TAG[index].to_string();
-					}
-					"进度" => {
-						finish = FILTER_FINISH[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				order = FILTER_ORDER[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let tag = filters.get("标签");
+	let finish = filters.get("进度");
+	let order = filters.get("排序");
 
 	let url = if query.is_empty() {
 		let mut url = format!("{}/category", WWW_URL);
@@ -75,9 +45,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	} else {
 		format!("{}/search/{}/{}", WWW_URL, encode_uri(query.clone()), page)
 	};
-	let html = Request::new(url, HttpMethod::Get)
-		.header("User-Agent", UA)
-		.html()?;
+	let html = Profile::MOBILE.request(url, HttpMethod::Get).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -86,15 +54,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select("a").attr("href").read());
 		let cover = if query.is_empty() {
 			item.select("a>img").attr("data-src").read()
 		} else {
@@ -118,8 +78,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
+	let html = Profile::MOBILE
+		.request(url.clone(), HttpMethod::Get)
 		.html()?;
 	let id = html
 		.html()
@@ -188,8 +148,8 @@ fn get_manga_details(id: String) -> Result<Manga> {
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/api/comic/chapter?mid={}", WWW_URL, id.clone());
-	let json = Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
+	let json = Profile::MOBILE
+		.request(url.clone(), HttpMethod::Get)
 		.json()?;
 	let data = json.as_object()?;
 	let list = data.get("data").as_array()?;
@@ -232,8 +192,8 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/chapter/{}", WWW_URL, chapter_id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
+	let html = Profile::MOBILE
+		.request(url.clone(), HttpMethod::Get)
 		.html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
@@ -253,3 +213,24 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	match path_segments(&url).as_slice() {
+		[.., kind, id] if kind == "comic" => Ok(deep_link(get_manga_details(id.clone())?, None)),
+		[.., kind, chapter_id] if kind == "chapter" => {
+			// Chapter pages only link back to their manga.
+			let url = format!("{}/chapter/{}", WWW_URL, chapter_id);
+			let html = Profile::MOBILE.request(url, HttpMethod::Get).html()?;
+			let manga_id = last_segment(html.select("a[href*='/comic/']").attr("href").read());
+			if manga_id.is_empty() {
+				return Err(unsupported());
+			}
+			Ok(deep_link(
+				get_manga_details(manga_id)?,
+				Some(chapter_id.clone()),
+			))
+		}
+		_ => Err(unsupported()),
+	}
+}
@@ -9,51 +9,37 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::{select_value, FilterValues},
+	id::html_slug,
+	link::{deep_link, unsupported},
+	request::gen_request,
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://www.wnacg01.cc";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
 
-const FILTER_CATEGORY: [&str; 4] = ["", "5", "6", "7"];
+const FILTERS: &str = include_str!("../res/filters.json");
 const FILTER_CATEGORY_5: [&str; 4] = ["5", "1", "12", "16"];
 const FILTER_CATEGORY_6: [&str; 4] = ["6", "9", "13", "17"];
 const FILTER_CATEGORY_7: [&str; 4] = ["7", "10", "14", "18"];
 
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
-}
-
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"类别" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					"语言" => match category.as_str() {
-						"5" => category = FILTER_CATEGORY_5[index].to_string(),
-						"6" => category = FILTER_CATEGORY_6[index].to_string(),
-						"7" => category = FILTER_CATEGORY_7[index].to_string(),
-						_ => continue,
-					},
-					_ => continue,
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let language = filters.index("语言").unwrap_or_default();
+	// Each category has its own id per language, so those stay in code.
+	let category = match filters.get("类别").as_str() {
+		"5" => select_value(&FILTER_CATEGORY_5, language),
+		"6" => select_value(&FILTER_CATEGORY_6, language),
+		"7" => select_value(&FILTER_CATEGORY_7, language),
+		category => category.to_string(),
+	};
 
 	let url = if query.is_empty() {
 		format!(
@@ -263,3 +249,24 @@ fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Every gallery is a single chapter whose ID is the album ID, e.g.
+	// `photos-index-aid-123.html` or `photos-slide-aid-123.html`.
+	let slug = match path_segments(&url).as_slice() {
+		[slug] => html_slug(slug),
+		_ => return Err(unsupported()),
+	};
+	let manga_id = match slug.split_once("-aid-") {
+		Some((_, id)) => id.to_string(),
+		None => return Err(unsupported()),
+	};
+	let chapter_id = if slug.starts_with("photos-index") {
+		None
+	} else {
+		Some(manga_id.clone())
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
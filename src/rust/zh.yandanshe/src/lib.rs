@@ -9,71 +9,37 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	id::last_segment,
+	link::{deep_link, unsupported},
+	request::Profile,
+	url::path_segments,
+};
 
 const WWW_URL: &str = "https://yandanshe.com";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
-
-const FILTER_CATEGORY: [&str; 3] = ["", "bl", "bg"];
-const FILTER_STATUS: [&str; 3] = ["", "lz", "wj"];
-const FILTER_TAG: [&str; 12] = [
-	"",
-	"韓漫",
-	"會員專區",
-	"abo",
-	"幻想",
-	"職場",
-	"校園",
-	"娛樂圈",
-	"異世界",
-	"强制",
-	"台漫",
-	"百合",
-];
-const FILTER_SORT: [&str; 2] = ["time", "like"];
+
+/// The image host still gets the Chrome 131 UA this source always sent,
+/// rather than the newer one in [`Profile::DESKTOP`].
+const PROFILE: Profile = Profile {
+	user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36",
+	headers: &[],
+};
+
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category = String::new();
-	let mut status = String::new();
-	let mut tag = String::new();
-	let mut sort = String::from("time");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"分类" => {
-						category = FILTER_CATEGORY[index].to_string();
-					}
-					"状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					"标签" => {
-						tag = FILTER_TAG[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				sort = FILTER_SORT[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let mut category = filters.get("分类");
+	let mut status = filters.get("状态");
+	let tag = filters.get("标签");
+	let sort = filters.get("排序");
 
 	if category.is_empty() || status.is_empty() {
 		category = String::new();
@@ -102,16 +68,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("h3>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select("h3>a").attr("href").read());
 		let cover = item.select(".thumbnail>a>img").attr("src").read();
 		let title = item.select("h3>a").text().read();
 		mangas.push(Manga {
@@ -250,5 +207,33 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", WWW_URL).header("User-Agent", UA);
+	PROFILE.apply(request).header("Referer", WWW_URL);
+}
+
+fn is_listing(segment: &str) -> bool {
+	let filters = match FilterValues::decode(FILTERS, Vec::new()) {
+		Ok(filters) => filters,
+		Err(_) => return false,
+	};
+	segment == "page"
+		|| segment == "tag"
+		|| filters.ids("分类").iter().any(|category| {
+			filters
+				.ids("状态")
+				.iter()
+				.any(|status| format!("{}{}", category, status) == segment)
+		})
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[id] if !is_listing(id) => (id.clone(), None),
+		[id, chapter_id] if !is_listing(id) && chapter_id.chars().all(|c| c.is_ascii_digit()) => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		_ => return Err(unsupported()),
+	};
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
 }
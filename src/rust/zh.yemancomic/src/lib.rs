@@ -13,6 +13,7 @@ use aidoku::{
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{filter::select_value, id::last_segment};
 
 const WWW_URL: &str = "https://yemancomic.com";
 
@@ -77,13 +78,13 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
 					"题材" => {
-						category = FILTER_CATEGORY[index].to_string();
+						category = select_value(&FILTER_CATEGORY, index);
 					}
 					"地区" => {
-						region = FILTER_REGION[index].to_string();
+						region = select_value(&FILTER_REGION, index);
 					}
 					"进度" => {
-						status = FILTER_STATUS[index].to_string();
+						status = select_value(&FILTER_STATUS, index);
 					}
 					_ => continue,
 				}
@@ -112,16 +113,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".acgn-thumbnail")
-				.attr("href")
-				.read()
-				.split("/")
-				.filter(|a| !a.is_empty())
-				.map(|a| a.to_string())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = last_segment(item.select(".acgn-thumbnail").attr("href").read());
 			let cover = item.select(".acgn-thumbnail>img").attr("src").read();
 			let title = item
 				.select(".acgn-info>.acgn-title>a")
@@ -202,16 +194,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".acgn-thumbnail")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = last_segment(item.select(".acgn-thumbnail").attr("href").read());
 		let cover = item.select(".acgn-thumbnail>img").attr("src").read();
 		let title = item
 			.select(".acgn-info>.acgn-title>a")
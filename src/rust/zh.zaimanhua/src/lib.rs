@@ -9,76 +9,33 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	filter::FilterValues,
+	link::{deep_link, unsupported},
+	url::{path_segments, query_value},
+};
 
 const WWW_URL: &str = "https://m.zaimanhua.com";
 const API_URL: &str = "https://manhua.zaimanhua.com/api/v1";
 const APP_URL: &str = "https://manhua.zaimanhua.com/app/v1";
 const V4_APP_URL: &str = "https://v4api.zaimanhua.com/app/v1";
 
-const FILTER_STATUS: [&str; 3] = ["0", "1", "2"];
-const FILTER_AUDIENCE: [&str; 4] = ["0", "3262", "3263", "3264"];
-const FILTER_THEME: [&str; 23] = [
-	"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "11", "13", "14", "15", "16", "17", "18",
-	"19", "20", "21", "22", "23", "24",
-];
-const FILTER_CATE: [&str; 3] = ["0", "1", "2"];
-const FILTER_FIRST_LETTER: [&str; 28] = [
-	"", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r",
-	"s", "t", "u", "v", "w", "x", "y", "z", "9",
-];
-const FILTER_SORT_TYPE: [&str; 1] = ["0"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut status = String::from("0");
-	let mut audience = String::from("0");
-	let mut theme = String::from("0");
-	let mut cate = String::from("0");
-	let mut first_letter = String::from("");
-	let mut sort_type = String::from("0");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"状态" => {
-						status = FILTER_STATUS[index].to_string();
-					}
-					"受众" => {
-						audience = FILTER_AUDIENCE[index].to_string();
-					}
-					"题材" => {
-						theme = FILTER_THEME[index].to_string();
-					}
-					"类别" => {
-						cate = FILTER_CATE[index].to_string();
-					}
-					"字母" => {
-						first_letter = FILTER_FIRST_LETTER[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				sort_type = FILTER_SORT_TYPE[index].to_string();
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let status = filters.get("状态");
+	let audience = filters.get("受众");
+	let theme = filters.get("题材");
+	let cate = filters.get("类别");
+	let first_letter = filters.get("字母");
+	let sort_type = filters.get("排序");
 
 	let url = if query.is_empty() {
 		format!("{}/comic1/filter?sortType={}&page={}&size=18&status={}&audience={}&theme={}&cate={}&firstLetter={}",
@@ -341,3 +298,18 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (manga_id, chapter_id) = match path_segments(&url).as_slice() {
+		[.., kind] if kind == "detail" => (query_value(&url, "id"), None),
+		[.., kind] if kind == "page" => (
+			query_value(&url, "comic_id"),
+			query_value(&url, "chapter_id"),
+		),
+		_ => return Err(unsupported()),
+	};
+	let manga_id = manga_id.ok_or_else(unsupported)?;
+
+	Ok(deep_link(get_manga_details(manga_id)?, chapter_id))
+}
@@ -1,20 +1,17 @@
 use aidoku::{
-	error::{AidokuError, AidokuErrorKind},
 	prelude::format,
 	std::{
 		defaults::{defaults_get, defaults_set},
 		html::Node,
-		net::{HttpMethod, Request},
+		net::HttpMethod,
 		StringRef,
 	},
 };
 use alloc::{string::String, vec::Vec};
-
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
-
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
-}
+use zh_common::{
+	error::{check_response, Error, SourceError},
+	request::gen_request,
+};
 
 fn handle_cookie_header(cookie_header: String) -> String {
 	return cookie_header
@@ -26,22 +23,23 @@ fn handle_cookie_header(cookie_header: String) -> String {
 		.join(";");
 }
 
-fn get_default(key: &str) -> Result<String, AidokuError> {
+fn get_default(key: &str) -> Result<String, Error> {
 	Ok(defaults_get(key)?.as_string()?.read())
 }
 
 pub fn get_url() -> String {
-	get_default("url").unwrap()
+	get_default("url").unwrap_or_default()
 }
 
-pub fn get_html(url: String) -> Result<Node, AidokuError> {
+pub fn get_html(url: String) -> Result<Node, Error> {
 	let default_cookie = get_default("cookie")?;
 	let request = gen_request(url.clone(), HttpMethod::Get).header("Cookie", &default_cookie);
 
 	request.send();
+	check_response(&request)?;
 
 	let cookie_header = request.get_header("set-cookie").unwrap_or_default().read();
-	let html = request.html().unwrap();
+	let html = request.html()?;
 
 	if html
 		.select("#main_message #messagetext>p")
@@ -49,16 +47,17 @@ pub fn get_html(url: String) -> Result<Node, AidokuError> {
 		.read()
 		.contains("仅限用户观看，请先登录")
 	{
-		let username = get_default("username")?;
-		let password = get_default("password")?;
+		let username = get_default("username").unwrap_or_default();
+		let password = get_default("password").unwrap_or_default();
 
 		if username.is_empty() || password.is_empty() {
-			return Err(AidokuError {
-				reason: AidokuErrorKind::DefaultNotFound,
-			});
+			return Err(SourceError::Auth.into());
 		}
 
 		let formhash = html.select("input[name=formhash]").attr("value").read();
+		if formhash.is_empty() {
+			return Err(SourceError::Redesign.into());
+		}
 		let login_cookie = handle_cookie_header(cookie_header);
 		let body = format!(
 			"username={}&cookietime=2592000&password={}&formhash={}&quickforward=yes&handlekey=ls",
@@ -78,9 +77,7 @@ pub fn get_html(url: String) -> Result<Node, AidokuError> {
 			.read();
 
 		if !new_cookie_header.contains("auth") {
-			return Err(AidokuError {
-				reason: AidokuErrorKind::DefaultNotFound,
-			});
+			return Err(SourceError::Auth.into());
 		}
 
 		let new_cookie = handle_cookie_header(new_cookie_header);
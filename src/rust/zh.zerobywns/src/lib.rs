@@ -6,70 +6,31 @@ use aidoku::{
 	helpers::{substring::Substring, uri::encode_uri},
 	prelude::*,
 	std::{json, net::Request, String, Vec},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	error::{self, page_list, SourceError},
+	filter::FilterValues,
+	link::{deep_link, unsupported},
+	url::query_value,
+};
 
 mod helper;
 
-const FILTER_CATEGORY_ID: [&str; 15] = [
-	"", "1", "15", "32", "6", "13", "28", "31", "22", "23", "26", "29", "34", "35", "36",
-];
-const FILTER_JINDU: [&str; 3] = ["", "0", "1"];
-const FILTER_SHUXING: [&str; 4] = ["", "一半中文一半生肉", "全生肉", "全中文"];
-const FILTER_AREA: [&str; 2] = ["", "日本"];
-const FILTER_ODFIE: [&str; 2] = ["addtime", "edittime"];
+const FILTERS: &str = include_str!("../res/filters.json");
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
-	let mut query = String::new();
-	let mut category_id = String::new();
-	let mut jindu = String::new();
-	let mut shuxing = String::new();
-	let mut area = String::new();
-	let mut odfie = String::from("addtime");
-	let mut order = String::from("desc");
-
-	for filter in filters {
-		match filter.kind {
-			FilterType::Title => {
-				query = filter.value.as_string()?.read();
-			}
-			FilterType::Select => {
-				let index = filter.value.as_int()? as usize;
-				match filter.name.as_str() {
-					"分类" => {
-						category_id = FILTER_CATEGORY_ID[index].to_string();
-					}
-					"进度" => {
-						jindu = FILTER_JINDU[index].to_string();
-					}
-					"性质" => {
-						shuxing = FILTER_SHUXING[index].to_string();
-					}
-					"地区" => {
-						area = FILTER_AREA[index].to_string();
-					}
-					_ => continue,
-				}
-			}
-			FilterType::Sort => {
-				let value = match filter.value.as_object() {
-					Ok(value) => value,
-					Err(_) => continue,
-				};
-				let index = value.get("index").as_int()? as usize;
-				let ascending = value.get("ascending").as_bool().unwrap_or(false);
-				odfie = FILTER_ODFIE[index].to_string();
-
-				if ascending {
-					order = String::from("asc");
-				}
-			}
-			_ => continue,
-		}
-	}
+	let filters = FilterValues::decode(FILTERS, filters)?;
+	let query = filters.query();
+	let category_id = filters.get("分类");
+	let jindu = filters.get("进度");
+	let shuxing = filters.get("性质");
+	let area = filters.get("地区");
+	let odfie = filters.get("排序");
+	let order = if filters.ascending() { "asc" } else { "desc" };
 
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
@@ -201,7 +162,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let categories = html
 		.select(".uk-margin-left>ul>li>.cl>a[href*='category']")
 		.array()
-		.map(|a| a.as_node().unwrap().text().read())
+		.filter_map(|a| Some(a.as_node().ok()?.text().read()))
 		.collect::<Vec<String>>();
 	let status = match html
 		.select(".uk-margin-left>ul>li>.cl>span:nth-child(6)")
@@ -277,6 +238,10 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
+	page_list(fetch_page_list(chapter_id))
+}
+
+fn fetch_page_list(chapter_id: String) -> error::Result<Vec<Page>> {
 	let url = format!(
 		"{}/plugin.php?id=jameson_manhua&a=read&zjid={}",
 		helper::get_url(),
@@ -286,10 +251,9 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	let text = html.html().read();
 	let list = text
 		.substring_after("let listimg=")
-		.unwrap()
-		.substring_before(";")
-		.unwrap();
-	let list = json::parse(list).unwrap().as_array()?;
+		.and_then(|list| list.substring_before(";"))
+		.ok_or(SourceError::Redesign)?;
+	let list = json::parse(list)?.as_array()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in list.enumerate() {
@@ -313,3 +277,22 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", &helper::get_url());
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	if let Some(manga_id) = query_value(&url, "kuid") {
+		return Ok(deep_link(get_manga_details(manga_id)?, None));
+	}
+	let chapter_id = query_value(&url, "zjid").ok_or_else(unsupported)?;
+	// Chapter pages only link back to their manga.
+	let url = format!(
+		"{}/plugin.php?id=jameson_manhua&a=read&zjid={}",
+		helper::get_url(),
+		chapter_id
+	);
+	let html = helper::get_html(url)?;
+	let manga_id = html.select("a[href*='kuid=']").attr("href").read();
+	let manga_id = query_value(&manga_id, "kuid").ok_or_else(unsupported)?;
+
+	Ok(deep_link(get_manga_details(manga_id)?, Some(chapter_id)))
+}